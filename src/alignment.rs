@@ -0,0 +1,408 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+
+// lines up 3D point clouds that share some points but were each measured from their own
+// position and orientation, like the scanner reports of day 19
+
+pub type Point = (i32, i32, i32);
+
+// one of the 24 ways to turn a scanner, as the matrix that takes its coordinates to ours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation(pub [[i32; 3]; 3]);
+
+// rotate, then translate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Point,
+}
+
+// text formats for point cloud viewers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudFormat {
+    Ply,
+    // one point per line, "x y z" followed by the other properties
+    Xyz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    // how many points two clouds need in common before we believe they overlap
+    pub min_overlap: usize,
+}
+
+// a point cloud, along with the distances between its points, which don't depend on
+// where it was measured from and so can be compared between clouds before aligning them
+#[derive(Debug, Clone)]
+pub struct Scan {
+    points: Vec<Point>,
+    // squared distances from each point to every other point, sorted
+    distances: Vec<Vec<i64>>,
+    // how many pairs of points are each squared distance apart
+    all_distances: HashMap<i64, usize>,
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    // every signed permutation of the axes that doesn't mirror, identity first
+    pub fn all() -> Vec<Rotation> {
+        (0..3)
+            .permutations(3)
+            .flat_map(|axes| {
+                (0..8).map(move |signs| {
+                    let mut matrix = [[0; 3]; 3];
+                    for (row, axis) in axes.iter().enumerate() {
+                        matrix[row][*axis] = if signs >> row & 1 == 0 { 1 } else { -1 };
+                    }
+                    Rotation(matrix)
+                })
+            })
+            .filter(|rotation| rotation.determinant() == 1)
+            .collect()
+    }
+
+    fn determinant(&self) -> i32 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, (x, y, z): Point) -> Point {
+        let row = |r: &[i32; 3]| r[0] * x + r[1] * y + r[2] * z;
+        (row(&self.0[0]), row(&self.0[1]), row(&self.0[2]))
+    }
+
+    // this rotation followed by `other`
+    pub fn then(&self, other: &Rotation) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, cells) in matrix.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| other.0[row][k] * self.0[k][col]).sum();
+            }
+        }
+        Rotation(matrix)
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: (0, 0, 0),
+    };
+
+    pub fn apply(&self, point: Point) -> Point {
+        let (x, y, z) = self.rotation.apply(point);
+        let (tx, ty, tz) = self.translation;
+        (x + tx, y + ty, z + tz)
+    }
+
+    // this transform followed by `other`
+    pub fn then(&self, other: &Transform) -> Transform {
+        Transform {
+            rotation: self.rotation.then(&other.rotation),
+            translation: other.apply(self.translation),
+        }
+    }
+}
+
+impl FromStr for CloudFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> std::result::Result<Self, Self::Err> {
+        match format {
+            "ply" => Ok(CloudFormat::Ply),
+            "xyz" => Ok(CloudFormat::Xyz),
+            _ => Err(format!("unknown point cloud format: {}", format)),
+        }
+    }
+}
+
+// day 19 says 12
+impl Default for Config {
+    fn default() -> Self {
+        Config { min_overlap: 12 }
+    }
+}
+
+impl Config {
+    // pairs of points the overlap has between them
+    fn min_shared_pairs(&self) -> usize {
+        self.min_overlap * self.min_overlap.saturating_sub(1) / 2
+    }
+}
+
+impl Scan {
+    pub fn new(points: Vec<Point>) -> Self {
+        let distance = |a: &Point, b: &Point| {
+            let d = |a: i32, b: i32| (a as i64 - b as i64).pow(2);
+            d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+        };
+        let distances: Vec<Vec<i64>> = points
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                points
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| i != *j)
+                    .map(|(_, b)| distance(a, b))
+                    .sorted()
+                    .collect()
+            })
+            .collect();
+        let mut all_distances = HashMap::new();
+        for (i, j) in (0..points.len()).tuple_combinations() {
+            *all_distances
+                .entry(distance(&points[i], &points[j]))
+                .or_insert(0) += 1;
+        }
+        Self {
+            points,
+            distances,
+            all_distances,
+        }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    // the transform that takes `other`'s coordinates into ours, if at least
+    // `config.min_overlap` of its points land on ours
+    pub fn find_transform(&self, other: &Scan, config: &Config) -> Option<Transform> {
+        let shared_pairs: usize = self
+            .all_distances
+            .iter()
+            .filter_map(|(distance, count)| Some(*count.min(other.all_distances.get(distance)?)))
+            .sum();
+        if shared_pairs < config.min_shared_pairs() {
+            return None;
+        }
+
+        // points that are the same distances from enough other points are probably the
+        // same point
+        let candidates: Vec<(Point, Point)> = self
+            .points
+            .iter()
+            .zip(&self.distances)
+            .flat_map(|(ours, our_distances)| {
+                other
+                    .points
+                    .iter()
+                    .zip(&other.distances)
+                    .filter(move |(_, their_distances)| {
+                        shared(our_distances, their_distances) + 1 >= config.min_overlap
+                    })
+                    .map(move |(theirs, _)| (*ours, *theirs))
+            })
+            .collect();
+        if candidates.len() < config.min_overlap {
+            return None;
+        }
+
+        let ours: HashSet<Point> = self.points.iter().copied().collect();
+        Rotation::all().into_iter().find_map(|rotation| {
+            // every true match agrees on the translation, so try the most popular ones
+            let mut votes: HashMap<Point, usize> = HashMap::new();
+            for (our_point, their_point) in &candidates {
+                let (x, y, z) = rotation.apply(*their_point);
+                let translation = (our_point.0 - x, our_point.1 - y, our_point.2 - z);
+                *votes.entry(translation).or_insert(0) += 1;
+            }
+            votes
+                .into_iter()
+                .filter(|(_, count)| *count >= config.min_overlap)
+                .map(|(translation, _)| Transform {
+                    rotation,
+                    translation,
+                })
+                .find(|transform| {
+                    other
+                        .points
+                        .iter()
+                        .filter(|point| ours.contains(&transform.apply(**point)))
+                        .count()
+                        >= config.min_overlap
+                })
+        })
+    }
+}
+
+// how many of the values in two sorted lists match up, counting repeats
+fn shared(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+// where one scan ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    // which cluster it's in, and the scan it was aligned against, which is None for the
+    // first scan of each cluster
+    pub cluster: usize,
+    pub parent: Option<usize>,
+    // takes its coordinates into its parent's
+    pub relative: Transform,
+    // takes its coordinates into those of the first scan of its cluster
+    pub transform: Transform,
+}
+
+// scans that overlap, directly or through others, end up in the same cluster. with
+// enough overlap there's just the one
+#[derive(Debug, Clone)]
+pub struct Alignment {
+    pub placements: Vec<Placement>,
+    // the scans in each cluster, in the order they were aligned
+    pub clusters: Vec<Vec<usize>>,
+}
+
+impl Alignment {
+    pub fn is_connected(&self) -> bool {
+        self.clusters.len() <= 1
+    }
+
+    // (parent, child) for every scan that was aligned against another
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.placements
+            .iter()
+            .enumerate()
+            .filter_map(|(scan, placement)| Some((placement.parent?, scan)))
+    }
+
+    // each cluster as a tree of which scan was aligned against which, one scan per line
+    pub fn graph(&self) -> String {
+        let mut lines = Vec::new();
+        for (cluster, scans) in self.clusters.iter().enumerate() {
+            lines.push(format!("cluster {}:", cluster));
+            self.graph_lines(scans[0], 1, &mut lines);
+        }
+        lines.join("\n")
+    }
+
+    fn graph_lines(&self, scan: usize, depth: usize, lines: &mut Vec<String>) {
+        let (x, y, z) = self.placements[scan].transform.translation;
+        lines.push(format!(
+            "{}{} at {},{},{}",
+            "  ".repeat(depth),
+            scan,
+            x,
+            y,
+            z
+        ));
+        let children: Vec<usize> = self
+            .edges()
+            .filter(|(parent, _)| *parent == scan)
+            .map(|(_, child)| child)
+            .collect();
+        for child in children {
+            self.graph_lines(child, depth + 1, lines);
+        }
+    }
+
+    // every distinct point in the coordinates of its cluster, along with the scan that saw
+    // it first, and the position of every scan. each point is "x y z scan cluster kind",
+    // where kind is 0 for points and 1 for the scans themselves
+    pub fn export(&self, scans: &[Scan], format: CloudFormat) -> String {
+        let mut seen = HashSet::new();
+        let mut vertices = Vec::new();
+        for (scan, placement) in self.placements.iter().enumerate() {
+            for point in scans[scan].points() {
+                let point = placement.transform.apply(*point);
+                if seen.insert((placement.cluster, point)) {
+                    vertices.push((point, scan, placement.cluster, 0));
+                }
+            }
+        }
+        for (scan, placement) in self.placements.iter().enumerate() {
+            vertices.push((placement.transform.translation, scan, placement.cluster, 1));
+        }
+
+        let mut text = String::new();
+        if format == CloudFormat::Ply {
+            text += "ply\nformat ascii 1.0\n";
+            text += &format!("element vertex {}\n", vertices.len());
+            for property in ["x", "y", "z", "scan", "cluster"] {
+                text += &format!("property int {}\n", property);
+            }
+            text += "property uchar kind\nend_header\n";
+        }
+        for ((x, y, z), scan, cluster, kind) in vertices {
+            text += &format!("{} {} {} {} {} {}\n", x, y, z, scan, cluster, kind);
+        }
+        text
+    }
+
+    // says which scans couldn't be aligned with which, if any
+    pub fn diagnostic(&self) -> Option<String> {
+        if self.is_connected() {
+            return None;
+        }
+        Some(format!(
+            "scans split into {} clusters that don't overlap each other: {}",
+            self.clusters.len(),
+            self.clusters
+                .iter()
+                .map(|scans| format!("[{}]", scans.iter().sorted().join(", ")))
+                .join(", ")
+        ))
+    }
+}
+
+// aligns every scan it can against the first, then starts a new cluster from the first
+// scan left over, and so on until every scan is in a cluster
+pub fn align(scans: &[Scan], config: &Config) -> Alignment {
+    let mut placements: Vec<Option<Placement>> = vec![None; scans.len()];
+    let mut clusters = Vec::new();
+    while let Some(root) = placements.iter().position(|p| p.is_none()) {
+        let cluster = clusters.len();
+        placements[root] = Some(Placement {
+            cluster,
+            parent: None,
+            relative: Transform::IDENTITY,
+            transform: Transform::IDENTITY,
+        });
+        let mut members = vec![root];
+        let mut queue = VecDeque::from([root]);
+        while let Some(aligned) = queue.pop_front() {
+            let to_root = placements[aligned].unwrap().transform;
+            for next in 0..scans.len() {
+                if placements[next].is_some() {
+                    continue;
+                }
+                if let Some(relative) = scans[aligned].find_transform(&scans[next], config) {
+                    placements[next] = Some(Placement {
+                        cluster,
+                        parent: Some(aligned),
+                        relative,
+                        transform: relative.then(&to_root),
+                    });
+                    members.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        clusters.push(members);
+    }
+    Alignment {
+        placements: placements.into_iter().flatten().collect(),
+        clusters,
+    }
+}
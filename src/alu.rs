@@ -0,0 +1,375 @@
+use std::{fmt, ops::Index, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    error::Result,
+    input::{Input, Line},
+};
+
+// the arithmetic logic unit from day 24, which runs MONAD and any other program written
+// for it
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+// w, x, y and z, in that order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Registers(pub [i64; 4]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AluError {
+    // an inp ran after the last input value was used up
+    InputExhausted { instruction: usize },
+    // a div by zero
+    DivideByZero { instruction: usize },
+    // a mod of a negative number or by anything that isn't positive
+    InvalidMod { instruction: usize, a: i64, b: i64 },
+    // a result that doesn't fit in 64 bits
+    Overflow { instruction: usize },
+}
+
+pub const REGISTERS: [Register; 4] = [Register::W, Register::X, Register::Y, Register::Z];
+
+impl Register {
+    fn index(self) -> usize {
+        match self {
+            Register::W => 0,
+            Register::X => 1,
+            Register::Y => 2,
+            Register::Z => 3,
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(register: &str) -> std::result::Result<Self, Self::Err> {
+        match register {
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(format!("unknown register {:?}", register)),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Operand {
+    type Err = String;
+
+    fn from_str(operand: &str) -> std::result::Result<Self, Self::Err> {
+        match operand.parse() {
+            Ok(register) => Ok(Operand::Register(register)),
+            Err(_) => operand
+                .parse()
+                .map(Operand::Number)
+                .map_err(|_| format!("expected a register or a number, not {:?}", operand)),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl Instruction {
+    fn parse(line: &Line) -> Result<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (&name, operands) = fields
+            .split_first()
+            .ok_or_else(|| line.error("expected an instruction"))?;
+        let expected = match name {
+            "inp" => 1,
+            "add" | "mul" | "div" | "mod" | "eql" => 2,
+            _ => return Err(line.error(format!("unknown instruction {:?}", name))),
+        };
+        if operands.len() != expected {
+            return Err(line.error(format!(
+                "{} takes {} operands, not {}",
+                name,
+                expected,
+                operands.len()
+            )));
+        }
+        let a = line.parse_field(operands[0])?;
+        if name == "inp" {
+            return Ok(Instruction::Inp(a));
+        }
+        let b = line.parse_field(operands[1])?;
+        match name {
+            "add" => Ok(Instruction::Add(a, b)),
+            "mul" => Ok(Instruction::Mul(a, b)),
+            "div" => Ok(Instruction::Div(a, b)),
+            "mod" => Ok(Instruction::Mod(a, b)),
+            _ => Ok(Instruction::Eql(a, b)),
+        }
+    }
+
+    // the register the instruction writes to
+    pub fn target(&self) -> Register {
+        match *self {
+            Instruction::Inp(a)
+            | Instruction::Add(a, _)
+            | Instruction::Mul(a, _)
+            | Instruction::Div(a, _)
+            | Instruction::Mod(a, _)
+            | Instruction::Eql(a, _) => a,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Inp(a) => write!(f, "inp {}", a),
+            Instruction::Add(a, b) => write!(f, "add {} {}", a, b),
+            Instruction::Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Instruction::Div(a, b) => write!(f, "div {} {}", a, b),
+            Instruction::Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Instruction::Eql(a, b) => write!(f, "eql {} {}", a, b),
+        }
+    }
+}
+
+impl Index<Register> for Registers {
+    type Output = i64;
+
+    fn index(&self, register: Register) -> &i64 {
+        &self.0[register.index()]
+    }
+}
+
+impl Registers {
+    pub fn set(&mut self, register: Register, value: i64) {
+        self.0[register.index()] = value;
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self[register],
+            Operand::Number(number) => number,
+        }
+    }
+
+    // runs a single instruction, the `index`th of its program
+    pub fn execute<I>(
+        &mut self,
+        index: usize,
+        instruction: &Instruction,
+        inputs: &mut I,
+    ) -> std::result::Result<(), AluError>
+    where
+        I: Iterator<Item = i64>,
+    {
+        let overflow = AluError::Overflow { instruction: index };
+        let value = match *instruction {
+            Instruction::Inp(_) => inputs
+                .next()
+                .ok_or(AluError::InputExhausted { instruction: index })?,
+            Instruction::Add(a, b) => self[a].checked_add(self.value(b)).ok_or(overflow)?,
+            Instruction::Mul(a, b) => self[a].checked_mul(self.value(b)).ok_or(overflow)?,
+            Instruction::Div(a, b) => match self.value(b) {
+                0 => return Err(AluError::DivideByZero { instruction: index }),
+                // truncates towards zero, like the puzzle asks
+                b => self[a].checked_div(b).ok_or(overflow)?,
+            },
+            Instruction::Mod(a, b) => match (self[a], self.value(b)) {
+                (a, b) if a >= 0 && b > 0 => a % b,
+                (a, b) => {
+                    return Err(AluError::InvalidMod {
+                        instruction: index,
+                        a,
+                        b,
+                    })
+                }
+            },
+            Instruction::Eql(a, b) => (self[a] == self.value(b)) as i64,
+        };
+        self.set(instruction.target(), value);
+        trace!("{:>4}: {:<12} {}", index, instruction.to_string(), self);
+        Ok(())
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = REGISTERS
+            .iter()
+            .map(|register| format!("{}={}", register, self[*register]));
+        write!(f, "{}", values.format(" "))
+    }
+}
+
+impl fmt::Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AluError::InputExhausted { instruction } => {
+                write!(f, "instruction {}: ran out of input", instruction)
+            }
+            AluError::DivideByZero { instruction } => {
+                write!(f, "instruction {}: division by zero", instruction)
+            }
+            AluError::InvalidMod { instruction, a, b } => {
+                write!(f, "instruction {}: can't take {} mod {}", instruction, a, b)
+            }
+            AluError::Overflow { instruction } => {
+                write!(f, "instruction {}: result overflows 64 bits", instruction)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AluError {}
+
+pub fn parse_program<I>(input: I) -> Result<Vec<Instruction>>
+where
+    I: Input,
+{
+    input
+        .into_lines()?
+        .map(|line| Instruction::parse(&line?))
+        .collect()
+}
+
+// the program as text again, one instruction per line
+pub fn assemble(program: &[Instruction]) -> String {
+    program
+        .iter()
+        .map(|instruction| format!("{}\n", instruction))
+        .collect()
+}
+
+// runs the program from all registers being zero. with trace logging on, prints the
+// registers after each instruction
+pub fn run<I>(program: &[Instruction], inputs: I) -> std::result::Result<Registers, AluError>
+where
+    I: IntoIterator<Item = i64>,
+{
+    run_from(Registers::default(), program, inputs)
+}
+
+// runs the program from the given registers, e.g. to pick up part way through
+pub fn run_from<I>(
+    mut registers: Registers,
+    program: &[Instruction],
+    inputs: I,
+) -> std::result::Result<Registers, AluError>
+where
+    I: IntoIterator<Item = i64>,
+{
+    let mut inputs = inputs.into_iter();
+    for (index, instruction) in program.iter().enumerate() {
+        registers.execute(index, instruction, &mut inputs)?;
+    }
+    Ok(registers)
+}
+
+// the registers after each instruction of the program
+pub fn run_traced<I>(
+    program: &[Instruction],
+    inputs: I,
+) -> std::result::Result<Vec<Registers>, AluError>
+where
+    I: IntoIterator<Item = i64>,
+{
+    let mut inputs = inputs.into_iter();
+    let mut registers = Registers::default();
+    program
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            registers.execute(index, instruction, &mut inputs)?;
+            Ok(registers)
+        })
+        .collect()
+}
+
+// which registers the program's result depends on when it starts, given the ones that
+// matter once it's done. anything else can be changed beforehand without making a
+// difference, e.g. x and y before a block of MONAD, which zeroes them before use.
+// whether the program fails counts as part of its result, so an instruction that can
+// fail keeps what it reads live even if nothing uses what it writes
+pub fn live_registers(program: &[Instruction], live_out: &[Register]) -> Vec<Register> {
+    let mut live = [false; 4];
+    for register in live_out {
+        live[register.index()] = true;
+    }
+    for instruction in program.iter().rev() {
+        let target = instruction.target().index();
+        if !live[target] && !can_fail(instruction) {
+            continue;
+        }
+        live[target] = false;
+        match *instruction {
+            Instruction::Inp(_) | Instruction::Mul(_, Operand::Number(0)) => {}
+            Instruction::Add(a, b)
+            | Instruction::Mul(a, b)
+            | Instruction::Div(a, b)
+            | Instruction::Mod(a, b)
+            | Instruction::Eql(a, b) => {
+                live[a.index()] = true;
+                if let Operand::Register(b) = b {
+                    live[b.index()] = true;
+                }
+            }
+        }
+    }
+    REGISTERS
+        .iter()
+        .copied()
+        .filter(|register| live[register.index()])
+        .collect()
+}
+
+// whether running the instruction can raise an AluError that depends on the registers
+fn can_fail(instruction: &Instruction) -> bool {
+    match *instruction {
+        Instruction::Inp(_) | Instruction::Eql(_, _) => false,
+        Instruction::Add(_, Operand::Number(0)) => false,
+        Instruction::Mul(_, Operand::Number(0)) | Instruction::Mul(_, Operand::Number(1)) => false,
+        Instruction::Add(_, _)
+        | Instruction::Mul(_, _)
+        | Instruction::Div(_, _)
+        | Instruction::Mod(_, _) => true,
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use crate::error::{Error, Result};
+
+// moves the cursor home and clears the screen, so each frame replaces the last
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+// shows each rendered frame in place, `delay` apart
+pub fn play(frames: &[String], delay: Duration) -> Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let last = frames.len().saturating_sub(1);
+    for (generation, frame) in frames.iter().enumerate() {
+        write!(
+            out,
+            "{}{}\ngeneration {}/{}\n",
+            CLEAR,
+            frame.trim_end_matches('\n'),
+            generation,
+            last
+        )
+        .and_then(|_| out.flush())
+        .map_err(|error| Error::Io {
+            source: "stdout".to_string(),
+            error,
+        })?;
+        if generation < last {
+            thread::sleep(delay);
+        }
+    }
+    Ok(())
+}
+
+// writes frame_0000.<extension>, frame_0001.<extension>, ... into `dir`
+pub fn export(frames: &[String], dir: &Path, extension: &str) -> Result<()> {
+    let io_error = |path: &Path| {
+        let source = path.display().to_string();
+        move |error| Error::Io { source, error }
+    };
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    for (generation, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("frame_{:04}.{}", generation, extension));
+        let mut contents = frame.clone();
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        fs::write(&path, contents).map_err(io_error(&path))?;
+    }
+    Ok(())
+}
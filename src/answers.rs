@@ -0,0 +1,140 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    error::Result,
+    input::Line,
+    store::{self, Key, Record},
+};
+
+pub struct Entry {
+    pub accepted: Option<String>,
+    pub last: String,
+    pub elapsed: Duration,
+}
+
+pub enum Verdict {
+    // the answer matches the accepted one
+    Correct,
+    // the answer doesn't match the accepted one, which is included
+    Wrong(String),
+    // no answer has been accepted for this input yet
+    Unverified,
+}
+
+// every answer we've computed, kept per day, part and input as the microseconds taken,
+// the accepted answer (empty if none) and the last answer
+pub struct Answers {
+    path: PathBuf,
+    entries: BTreeMap<Key, Entry>,
+}
+
+impl Record for Entry {
+    const FIELDS: usize = 3;
+
+    fn parse(line: &Line, fields: &[&str]) -> Result<Self> {
+        Ok(Entry {
+            accepted: match fields[1] {
+                "" => None,
+                accepted => Some(unescape(accepted)),
+            },
+            last: unescape(fields[2]),
+            elapsed: Duration::from_micros(line.parse_field(fields[0])?),
+        })
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.elapsed.as_micros().to_string(),
+            self.accepted.as_deref().map(escape).unwrap_or_default(),
+            escape(&self.last),
+        ]
+    }
+}
+
+impl Answers {
+    // a missing store is just an empty one
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let entries = store::load(&path)?;
+        Ok(Self { path, entries })
+    }
+
+    pub fn get(&self, day: u32, part: u32, hash: u64) -> Option<&Entry> {
+        self.entries.get(&(day, part, hash))
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (u32, u32, u64, &Entry)> {
+        self.entries
+            .iter()
+            .map(|(&(day, part, hash), entry)| (day, part, hash, entry))
+    }
+
+    // remembers the latest answer and how long it took, and checks it against the accepted one
+    pub fn record(
+        &mut self,
+        day: u32,
+        part: u32,
+        hash: u64,
+        answer: &str,
+        elapsed: Duration,
+    ) -> Verdict {
+        let entry = self
+            .entries
+            .entry((day, part, hash))
+            .or_insert_with(|| Entry {
+                accepted: None,
+                last: String::new(),
+                elapsed,
+            });
+        entry.last = answer.to_string();
+        entry.elapsed = elapsed;
+        match &entry.accepted {
+            None => Verdict::Unverified,
+            Some(accepted) if accepted == answer => Verdict::Correct,
+            Some(accepted) => Verdict::Wrong(accepted.clone()),
+        }
+    }
+
+    pub fn accept(&mut self, day: u32, part: u32, hash: u64, answer: &str) {
+        if let Some(entry) = self.entries.get_mut(&(day, part, hash)) {
+            entry.accepted = Some(answer.to_string());
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(&self.path, &self.entries)
+    }
+}
+
+// answers like day 13's are multi-line, so they need escaping to fit on one line
+fn escape(answer: &str) -> String {
+    answer
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn unescape(field: &str) -> String {
+    let mut answer = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            answer.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => answer.push('\n'),
+            Some('t') => answer.push('\t'),
+            Some(escaped) => answer.push(escaped),
+            None => answer.push('\\'),
+        }
+    }
+    answer
+}
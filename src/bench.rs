@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeMap,
+    io::Cursor,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    day18,
+    error::Result,
+    input::{self, AnyLines, Line, Lines},
+    parallel,
+    snailfish::{Flat, Number, Pair},
+    solution,
+    store::{self, Key, Record},
+};
+
+const NOISE: Duration = Duration::from_micros(200);
+
+// how long the parse and solve phases of one part took
+#[derive(Clone, Copy)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+pub enum Status {
+    New,
+    Ok,
+    Improved,
+    Regressed,
+}
+
+// the timings we compare against, kept per day, part and input as the parse and solve
+// microseconds. timings of different inputs, e.g. an example and a personal input, are
+// never compared
+pub struct Baseline {
+    path: PathBuf,
+    timings: BTreeMap<Key, Timing>,
+}
+
+// runs every part of each day `runs` times and keeps the fastest run, since anything
+// slower than that is noise from the rest of the machine. each timing comes with the
+// day, part and hash of the input it was timed on
+pub fn time_days<F>(days: &[u32], runs: u32, input_path: F) -> Result<Vec<(u32, u32, u64, Timing)>>
+where
+    F: Fn(u32) -> PathBuf,
+{
+    let mut timings = Vec::new();
+    for (day, solution) in solution::registry() {
+        if !days.is_empty() && !days.contains(&day) {
+            continue;
+        }
+        let path = input_path(day);
+        let text = input::read_all(&path)?;
+        let hash = store::hash(text.as_bytes());
+        for part in 1..=solution.parts() {
+            let mut fastest: Option<Timing> = None;
+            for _ in 0..runs.max(1) {
+                // reading the input isn't part of either phase
+                let lines: AnyLines = Lines::new(
+                    &input::source_name(&path),
+                    Box::new(Cursor::new(text.clone())),
+                );
+                let start = Instant::now();
+                let puzzle = solution.parse(lines)?;
+                let parsed = Instant::now();
+                solution.solve(puzzle.as_ref(), part)?;
+                let timing = Timing {
+                    parse: parsed - start,
+                    solve: parsed.elapsed(),
+                };
+                fastest = Some(match fastest {
+                    Some(fastest) if fastest.total() <= timing.total() => fastest,
+                    _ => timing,
+                });
+            }
+            timings.push((day, part, hash, fastest.unwrap()));
+        }
+    }
+    Ok(timings)
+}
+
+// times parsing and the part 2 all-pairs search of day 18 with each representation of
+// snailfish numbers, along with the answer each one gave
+pub fn time_snailfish(path: &Path, runs: u32) -> Result<Vec<(&'static str, Timing, u64)>> {
+    let text = input::read_all(path)?;
+    let source = input::source_name(path);
+    Ok(vec![
+        time_all_pairs::<Pair>("tree", &source, &text, runs)?,
+        time_all_pairs::<Flat>("flat", &source, &text, runs)?,
+    ])
+}
+
+fn time_all_pairs<N>(
+    name: &'static str,
+    source: &str,
+    text: &str,
+    runs: u32,
+) -> Result<(&'static str, Timing, u64)>
+where
+    N: Number + Sync,
+{
+    let mut fastest: Option<(Timing, u64)> = None;
+    for _ in 0..runs.max(1) {
+        let lines: AnyLines = Lines::new(source, Box::new(Cursor::new(text.to_string())));
+        let start = Instant::now();
+        let numbers = day18::parse_numbers::<N, _>(lines)?;
+        let parsed = Instant::now();
+        // not max_pair_magnitude, which reports the winning pair on every run
+        let answer =
+            day18::largest_sum(&numbers, parallel::threads()).map_or(0, |sum| sum.magnitude);
+        let timing = Timing {
+            parse: parsed - start,
+            solve: parsed.elapsed(),
+        };
+        fastest = Some(match fastest {
+            Some(fastest) if fastest.0.total() <= timing.total() => fastest,
+            _ => (timing, answer),
+        });
+    }
+    let (timing, answer) = fastest.unwrap();
+    Ok((name, timing, answer))
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+
+    // `threshold` is the fraction either way that still counts as unchanged. the fastest
+    // parts finish in microseconds, so differences under NOISE never count
+    pub fn compare(&self, baseline: &Timing, threshold: f64) -> Status {
+        let (current, baseline) = (self.total().as_secs_f64(), baseline.total().as_secs_f64());
+        if (current - baseline).abs() < NOISE.as_secs_f64() {
+            Status::Ok
+        } else if current > baseline * (1.0 + threshold) {
+            Status::Regressed
+        } else if current < baseline * (1.0 - threshold) {
+            Status::Improved
+        } else {
+            Status::Ok
+        }
+    }
+}
+
+impl Record for Timing {
+    const FIELDS: usize = 2;
+
+    fn parse(line: &Line, fields: &[&str]) -> Result<Self> {
+        Ok(Timing {
+            parse: Duration::from_micros(line.parse_field(fields[0])?),
+            solve: Duration::from_micros(line.parse_field(fields[1])?),
+        })
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.parse.as_micros().to_string(),
+            self.solve.as_micros().to_string(),
+        ]
+    }
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::New => "new",
+            Status::Ok => "ok",
+            Status::Improved => "improved",
+            Status::Regressed => "regressed",
+        }
+    }
+}
+
+impl Baseline {
+    // a missing baseline is just an empty one
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let timings = store::load(&path)?;
+        Ok(Self { path, timings })
+    }
+
+    pub fn get(&self, day: u32, part: u32, hash: u64) -> Option<&Timing> {
+        self.timings.get(&(day, part, hash))
+    }
+
+    pub fn update(&mut self, day: u32, part: u32, hash: u64, timing: Timing) {
+        self.timings.insert((day, part, hash), timing);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(&self.path, &self.timings)
+    }
+}
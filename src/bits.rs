@@ -0,0 +1,601 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+// the Buoyancy Interchange Transmission System packets from day 16
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal {
+    pub version: u32,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operator {
+    pub version: u32,
+    pub packet_type: u32,
+    pub subpackets: Vec<Packet>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Op(Operator),
+    Lit(Literal),
+}
+
+pub const LITERAL_TYPE: u32 = 4;
+
+// why a packet couldn't be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    // the packet's value doesn't fit in a u64
+    Overflow { expression: String },
+    // only possible for packets that were built rather than parsed
+    Arity { packet_type: u32, subpackets: usize },
+}
+
+impl Packet {
+    // checked arithmetic all the way down, so a value too big for a u64 is an error
+    // rather than wrapping around
+    pub fn evaluate(&self) -> Result<u64, EvalError> {
+        let operator = match self {
+            Packet::Lit(literal) => return Ok(literal.value),
+            Packet::Op(operator) => operator,
+        };
+        let values = operator
+            .subpackets
+            .iter()
+            .map(Packet::evaluate)
+            .collect::<Result<Vec<_>, _>>()?;
+        let overflow = || EvalError::Overflow {
+            expression: self.to_string(),
+        };
+        let value = match (operator.packet_type, &values[..]) {
+            (0, [_, ..]) => values
+                .iter()
+                .try_fold(0u64, |sum, value| sum.checked_add(*value))
+                .ok_or_else(overflow)?,
+            (1, [_, ..]) => values
+                .iter()
+                .try_fold(1u64, |product, value| product.checked_mul(*value))
+                .ok_or_else(overflow)?,
+            (2, [_, ..]) => *values.iter().min().unwrap(),
+            (3, [_, ..]) => *values.iter().max().unwrap(),
+            (5, [a, b]) => (a > b) as u64,
+            (6, [a, b]) => (a < b) as u64,
+            (7, [a, b]) => (a == b) as u64,
+            (packet_type, _) => {
+                return Err(EvalError::Arity {
+                    packet_type,
+                    subpackets: values.len(),
+                })
+            }
+        };
+        Ok(value)
+    }
+
+    pub fn version(&self) -> u32 {
+        match self {
+            Packet::Op(operator) => operator.version,
+            Packet::Lit(literal) => literal.version,
+        }
+    }
+
+    pub fn literal(version: u32, value: u64) -> Self {
+        Packet::Lit(Literal { version, value })
+    }
+
+    pub fn operator(version: u32, packet_type: u32, subpackets: Vec<Packet>) -> Self {
+        Packet::Op(Operator {
+            version,
+            packet_type,
+            subpackets,
+        })
+    }
+}
+
+// every offset is in bits from the start of the transmission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitsError {
+    // a character of the transmission isn't a hex digit
+    InvalidHex {
+        offset: usize,
+        found: char,
+    },
+    // the transmission ended `needed` bits short
+    Truncated {
+        offset: usize,
+        needed: usize,
+    },
+    // a literal has more groups than fit in a u64
+    LiteralOverflow {
+        offset: usize,
+    },
+    // the subpackets of a length type 0 operator ran past the length it gave
+    LengthMismatch {
+        offset: usize,
+        length: usize,
+        used: usize,
+    },
+    // comparisons need exactly two subpackets, everything else at least one
+    Arity {
+        offset: usize,
+        packet_type: u32,
+        subpackets: usize,
+    },
+    // there's something other than zero padding after the outermost packet
+    TrailingData {
+        offset: usize,
+    },
+    // a packet being encoded has a field that's too big for its width
+    FieldOverflow {
+        offset: usize,
+        field: &'static str,
+        value: u64,
+        bits: usize,
+    },
+    // a packet being encoded is an operator with the literal packet type
+    LiteralOperator {
+        offset: usize,
+    },
+}
+
+// how an operator says where its subpackets end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    // length type 0, the number of bits the subpackets take up
+    Bits,
+    // length type 1, the number of subpackets
+    Count,
+}
+
+impl fmt::Display for BitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitsError::InvalidHex { offset, found } => {
+                write!(f, "bit {}: {:?} is not a hex digit", offset, found)
+            }
+            BitsError::Truncated { offset, needed } => write!(
+                f,
+                "bit {}: transmission ended {} bits too early",
+                offset, needed
+            ),
+            BitsError::LiteralOverflow { offset } => {
+                write!(f, "bit {}: literal doesn't fit in 64 bits", offset)
+            }
+            BitsError::LengthMismatch {
+                offset,
+                length,
+                used,
+            } => write!(
+                f,
+                "bit {}: subpackets take {} bits but the operator says {}",
+                offset, used, length
+            ),
+            BitsError::Arity {
+                offset,
+                packet_type,
+                subpackets,
+            } => write!(
+                f,
+                "bit {}: operator type {} can't have {} subpackets",
+                offset, packet_type, subpackets
+            ),
+            BitsError::TrailingData { offset } => {
+                write!(f, "bit {}: unexpected data after the packet", offset)
+            }
+            BitsError::FieldOverflow {
+                offset,
+                field,
+                value,
+                bits,
+            } => write!(
+                f,
+                "bit {}: {} {} doesn't fit in {} bits",
+                offset, field, value, bits
+            ),
+            BitsError::LiteralOperator { offset } => write!(
+                f,
+                "bit {}: an operator can't have the literal packet type {}",
+                offset, LITERAL_TYPE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BitsError {}
+
+impl BitsError {
+    // the same error for bits that end up `by` bits further into the transmission
+    fn shifted(mut self, by: usize) -> Self {
+        match &mut self {
+            BitsError::InvalidHex { offset, .. }
+            | BitsError::Truncated { offset, .. }
+            | BitsError::LiteralOverflow { offset }
+            | BitsError::LengthMismatch { offset, .. }
+            | BitsError::Arity { offset, .. }
+            | BitsError::TrailingData { offset }
+            | BitsError::FieldOverflow { offset, .. }
+            | BitsError::LiteralOperator { offset } => *offset += by,
+        }
+        self
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow { expression } => {
+                write!(f, "{} overflows a 64 bit value", expression)
+            }
+            EvalError::Arity {
+                packet_type,
+                subpackets,
+            } => write!(
+                f,
+                "operator type {} can't have {} subpackets",
+                packet_type, subpackets
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+// the name of an operator's function, or the symbol of a comparison
+fn operator_name(packet_type: u32) -> &'static str {
+    match packet_type {
+        0 => "sum",
+        1 => "product",
+        2 => "min",
+        3 => "max",
+        5 => ">",
+        6 => "<",
+        7 => "==",
+        _ => "?",
+    }
+}
+
+fn is_comparison(packet: &Packet) -> bool {
+    matches!(packet, Packet::Op(operator) if (5..=7).contains(&operator.packet_type))
+}
+
+// as an expression, e.g. max(sum(1, 2), 3 < 4)
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Packet::Lit(literal) => return write!(f, "{}", literal.value),
+            Packet::Op(operator) => operator,
+        };
+        let name = operator_name(operator.packet_type);
+        match &operator.subpackets[..] {
+            [a, b] if is_comparison(self) => {
+                // comparisons of comparisons need brackets to read unambiguously
+                for (i, side) in [a, b].iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " {} ", name)?;
+                    }
+                    if is_comparison(side) {
+                        write!(f, "({})", side)?;
+                    } else {
+                        write!(f, "{}", side)?;
+                    }
+                }
+                Ok(())
+            }
+            subpackets => write!(f, "{}({})", name, subpackets.iter().format(", ")),
+        }
+    }
+}
+
+// one packet per line, indented by depth, with the bit each one starts at from `offsets`
+// (as returned by parse_with_offsets), or "?" where there isn't one
+pub fn tree(packet: &Packet, offsets: &[usize]) -> String {
+    let mut lines = Vec::new();
+    let mut offsets = offsets.iter();
+    tree_lines(packet, 0, &mut offsets, &mut lines);
+    lines.join("\n")
+}
+
+fn tree_lines<'a, I>(packet: &Packet, depth: usize, offsets: &mut I, lines: &mut Vec<String>)
+where
+    I: Iterator<Item = &'a usize>,
+{
+    let offset = offsets
+        .next()
+        .map_or("?".to_string(), |offset| offset.to_string());
+    let description = match packet {
+        Packet::Lit(literal) => format!("literal {}", literal.value),
+        Packet::Op(operator) => format!(
+            "{} of {}",
+            operator_name(operator.packet_type),
+            operator.subpackets.len()
+        ),
+    };
+    lines.push(format!(
+        "{}@{} v{} {}",
+        "  ".repeat(depth),
+        offset,
+        packet.version(),
+        description
+    ));
+    if let Packet::Op(operator) = packet {
+        for subpacket in &operator.subpackets {
+            tree_lines(subpacket, depth + 1, offsets, lines);
+        }
+    }
+}
+
+// reads big-endian bit fields, most significant bit of each byte first
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() * 8 - self.offset
+    }
+
+    // the next `n` bits, for n up to 64
+    pub fn read(&mut self, n: usize) -> Result<u64, BitsError> {
+        debug_assert!(n <= 64);
+        if n > self.remaining() {
+            return Err(BitsError::Truncated {
+                offset: self.offset,
+                needed: n - self.remaining(),
+            });
+        }
+        let mut value = 0;
+        for _ in 0..n {
+            let bit = self.bytes[self.offset / 8] >> (7 - self.offset % 8) & 1;
+            value = value << 1 | bit as u64;
+            self.offset += 1;
+        }
+        Ok(value)
+    }
+}
+
+// the inverse of BitReader
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    // appends the low `bits` bits of `value`, for up to 64 bits
+    pub fn write(&mut self, field: &'static str, value: u64, bits: usize) -> Result<(), BitsError> {
+        debug_assert!(bits <= 64);
+        if bits < 64 && value >> bits != 0 {
+            return Err(BitsError::FieldOverflow {
+                offset: self.offset,
+                field,
+                value,
+                bits,
+            });
+        }
+        for i in (0..bits).rev() {
+            if self.offset.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = (value >> i & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.offset % 8);
+            self.offset += 1;
+        }
+        Ok(())
+    }
+
+    // everything written so far, padded with zeros to a whole byte
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+// packs hex digits two to a byte, ignoring whitespace and accepting either case. an odd
+// number of digits is padded with a zero nibble
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, BitsError> {
+    let nibbles = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .enumerate()
+        .map(|(i, c)| {
+            c.to_digit(16)
+                .map(|nibble| nibble as u8)
+                .ok_or(BitsError::InvalidHex {
+                    offset: i * 4,
+                    found: c,
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(nibbles
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect())
+}
+
+pub fn parse_hex(hex: &str) -> Result<Packet, BitsError> {
+    parse(&decode_hex(hex)?)
+}
+
+// a whole transmission, which is one packet followed by zero padding
+pub fn parse(bytes: &[u8]) -> Result<Packet, BitsError> {
+    parse_with_offsets(bytes).map(|(packet, _)| packet)
+}
+
+// also returns where each packet starts, in the order `tree` visits them
+pub fn parse_with_offsets(bytes: &[u8]) -> Result<(Packet, Vec<usize>), BitsError> {
+    let mut reader = BitReader::new(bytes);
+    let mut offsets = Vec::new();
+    let packet = parse_packet(&mut reader, &mut offsets)?;
+    while reader.remaining() > 0 {
+        let offset = reader.offset();
+        if reader.read(1)? != 0 {
+            return Err(BitsError::TrailingData { offset });
+        }
+    }
+    Ok((packet, offsets))
+}
+
+pub fn parse_packet(reader: &mut BitReader, offsets: &mut Vec<usize>) -> Result<Packet, BitsError> {
+    let start = reader.offset();
+    offsets.push(start);
+    let version = reader.read(3)? as u32;
+    let packet_type = reader.read(3)? as u32;
+    if packet_type == LITERAL_TYPE {
+        let mut value: u64 = 0;
+        loop {
+            let more = reader.read(1)? == 1;
+            if value >> 60 != 0 {
+                return Err(BitsError::LiteralOverflow { offset: start });
+            }
+            value = value << 4 | reader.read(4)?;
+            if !more {
+                break;
+            }
+        }
+        return Ok(Packet::Lit(Literal { version, value }));
+    }
+
+    let mut subpackets = Vec::new();
+    if reader.read(1)? == 0 {
+        let length = reader.read(15)? as usize;
+        let subpackets_start = reader.offset();
+        while reader.offset() < subpackets_start + length {
+            subpackets.push(parse_packet(reader, offsets)?);
+        }
+        let used = reader.offset() - subpackets_start;
+        if used != length {
+            return Err(BitsError::LengthMismatch {
+                offset: start,
+                length,
+                used,
+            });
+        }
+    } else {
+        let count = reader.read(11)?;
+        for _ in 0..count {
+            subpackets.push(parse_packet(reader, offsets)?);
+        }
+    }
+
+    let arity_ok = match packet_type {
+        5..=7 => subpackets.len() == 2,
+        _ => !subpackets.is_empty(),
+    };
+    if !arity_ok {
+        return Err(BitsError::Arity {
+            offset: start,
+            packet_type,
+            subpackets: subpackets.len(),
+        });
+    }
+    Ok(Packet::Op(Operator {
+        version,
+        packet_type,
+        subpackets,
+    }))
+}
+
+// uses length type 1 wherever it fits, since its header is 4 bits shorter
+pub fn encode(packet: &Packet) -> Result<Vec<u8>, BitsError> {
+    encode_with(packet, &mut |operator| {
+        if operator.subpackets.len() < 1 << 11 {
+            LengthType::Count
+        } else {
+            LengthType::Bits
+        }
+    })
+}
+
+// `length_type` picks the length type of each operator, e.g. at random for fuzzing
+pub fn encode_with<F>(packet: &Packet, length_type: &mut F) -> Result<Vec<u8>, BitsError>
+where
+    F: FnMut(&Operator) -> LengthType,
+{
+    let mut writer = BitWriter::new();
+    write_packet(&mut writer, packet, length_type)?;
+    Ok(writer.into_bytes())
+}
+
+pub fn encode_hex(packet: &Packet) -> Result<String, BitsError> {
+    Ok(encode(packet)?
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect())
+}
+
+pub fn write_packet<F>(
+    writer: &mut BitWriter,
+    packet: &Packet,
+    length_type: &mut F,
+) -> Result<(), BitsError>
+where
+    F: FnMut(&Operator) -> LengthType,
+{
+    match packet {
+        Packet::Lit(Literal { version, value }) => {
+            writer.write("version", *version as u64, 3)?;
+            writer.write("packet type", LITERAL_TYPE as u64, 3)?;
+            // the fewest groups of 4 that hold the value, always at least one
+            let groups = (64 - value.leading_zeros() as usize).div_ceil(4);
+            for group in (0..groups.max(1)).rev() {
+                writer.write("literal group", (group > 0) as u64, 1)?;
+                writer.write("literal group", value >> (group * 4) & 0xf, 4)?;
+            }
+        }
+        Packet::Op(operator) if operator.packet_type == LITERAL_TYPE => {
+            return Err(BitsError::LiteralOperator {
+                offset: writer.offset(),
+            })
+        }
+        Packet::Op(operator) => {
+            writer.write("version", operator.version as u64, 3)?;
+            writer.write("packet type", operator.packet_type as u64, 3)?;
+            match length_type(operator) {
+                LengthType::Bits => {
+                    // the subpackets go into a writer of their own first to find their length,
+                    // so its offsets start after the length type and length fields
+                    let start = writer.offset() + 16;
+                    let mut subpackets = BitWriter::new();
+                    for subpacket in &operator.subpackets {
+                        write_packet(&mut subpackets, subpacket, length_type)
+                            .map_err(|err| err.shifted(start))?;
+                    }
+                    let length = subpackets.offset();
+                    writer.write("length type", 0, 1)?;
+                    writer.write("subpacket length", length as u64, 15)?;
+                    let mut reader = BitReader::new(&subpackets.bytes);
+                    while reader.offset() < length {
+                        let bits = (length - reader.offset()).min(64);
+                        writer.write("subpackets", reader.read(bits)?, bits)?;
+                    }
+                }
+                LengthType::Count => {
+                    writer.write("length type", 1, 1)?;
+                    writer.write("subpacket count", operator.subpackets.len() as u64, 11)?;
+                    for subpacket in &operator.subpackets {
+                        write_packet(writer, subpacket, length_type)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+// sets of points on an N dimensional integer grid, kept as disjoint axis-aligned boxes,
+// like the cubes of the reactor in day 22
+
+// every point from `min` up to but not including `max` on each axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxSet<const N: usize> {
+    // never overlap, and never empty
+    cuboids: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> Cuboid<N> {
+    // from (first, last) on each axis, like the puzzle's x=10..12
+    pub fn from_inclusive(ranges: [(i64, i64); N]) -> Self {
+        let mut cuboid = Cuboid {
+            min: [0; N],
+            max: [0; N],
+        };
+        for (axis, (first, last)) in ranges.iter().enumerate() {
+            cuboid.min[axis] = *first;
+            cuboid.max[axis] = last + 1;
+        }
+        cuboid
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|axis| self.min[axis] >= self.max[axis])
+    }
+
+    pub fn volume(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..N)
+            .map(|axis| (self.max[axis] - self.min[axis]) as u128)
+            .product()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut overlap = *self;
+        for axis in 0..N {
+            overlap.min[axis] = self.min[axis].max(other.min[axis]);
+            overlap.max[axis] = self.max[axis].min(other.max[axis]);
+        }
+        if overlap.is_empty() {
+            None
+        } else {
+            Some(overlap)
+        }
+    }
+
+    // what's left of this cuboid once `other` is taken out, as at most 2N disjoint
+    // cuboids. each axis in turn slices off whatever is below and above `other`
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.intersection(other).is_none() {
+            return vec![*self];
+        }
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..N {
+            if rest.min[axis] < other.min[axis] {
+                let mut below = rest;
+                below.max[axis] = other.min[axis];
+                pieces.push(below);
+                rest.min[axis] = other.min[axis];
+            }
+            if other.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = other.max[axis];
+                pieces.push(above);
+                rest.max[axis] = other.max[axis];
+            }
+        }
+        pieces
+    }
+}
+
+// the puzzle's notation, e.g. x=10..12,y=10..12,z=10..12, with axes after z numbered
+impl<const N: usize> fmt::Display for Cuboid<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for axis in 0..N {
+            if axis > 0 {
+                write!(f, ",")?;
+            }
+            match ["x", "y", "z"].get(axis) {
+                Some(name) => write!(f, "{}=", name)?,
+                None => write!(f, "{}=", axis)?,
+            }
+            write!(f, "{}..{}", self.min[axis], self.max[axis] - 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for BoxSet<N> {
+    fn default() -> Self {
+        Self {
+            cuboids: Vec::new(),
+        }
+    }
+}
+
+impl<const N: usize> From<Cuboid<N>> for BoxSet<N> {
+    fn from(cuboid: Cuboid<N>) -> Self {
+        let mut set = Self::new();
+        set.insert(cuboid);
+        set
+    }
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // disjoint cuboids that together make up the set
+    pub fn cuboids(&self) -> &[Cuboid<N>] {
+        &self.cuboids
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cuboids.is_empty()
+    }
+
+    pub fn volume(&self) -> u128 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.cuboids.iter().any(|cuboid| cuboid.contains(point))
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<N>) {
+        if cuboid.is_empty() {
+            return;
+        }
+        self.remove(&cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    pub fn remove(&mut self, cuboid: &Cuboid<N>) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|existing| existing.difference(cuboid))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for cuboid in &other.cuboids {
+            union.insert(*cuboid);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            cuboids: self
+                .cuboids
+                .iter()
+                .flat_map(|a| other.cuboids.iter().filter_map(move |b| a.intersection(b)))
+                .collect(),
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for cuboid in &other.cuboids {
+            difference.remove(cuboid);
+        }
+        difference
+    }
+
+    // how much of the set is inside `region`
+    pub fn volume_within(&self, region: &Cuboid<N>) -> u128 {
+        self.cuboids
+            .iter()
+            .filter_map(|cuboid| cuboid.intersection(region))
+            .map(|overlap| overlap.volume())
+            .sum()
+    }
+}
@@ -0,0 +1,886 @@
+use std::{
+    io::{self, BufRead, Cursor},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alignment::CloudFormat,
+    alu::{self, AluError},
+    animate,
+    answers::{Answers, Verdict},
+    bench::{self, Baseline, Status},
+    day11, day13,
+    day15::Day15,
+    day19, day20,
+    day22::{self, Cube, Reactor},
+    day25,
+    day9::Day9,
+    input::{self, AnyLines, Lines},
+    log::{self, Level},
+    parallel,
+    render::{Format, Shade},
+    solution::{self, Solution},
+    store,
+    util::Grid,
+};
+
+const USAGE: &str =
+    "usage: aoc run --day <1-25> --part <1|2> [--input <path>] [--accept] [--cached]
+       aoc bench [--day <n>]... [--runs <n>] [--threshold <percent>] [--personal] [--save-baseline]
+       aoc bench --snailfish [--runs <n>] [--personal]
+       aoc render --day <n> [--steps <n>] [--format <format>] [--input <path>]
+       aoc animate --day <n> [--steps <n>] [--fps <n>] [--format <format>] [--export <dir>] [--input <path>]
+       aoc export --day <19|22> [--format <format>] [--input <path>]
+       aoc query --day 22 [--point <x,y,z>]... [--region <x=..,y=..,z=..>]... [--input <path>]
+       aoc step --day 22 [--input <path>]
+       aoc alu [--inputs <n,n,...>] [--digits <digits>] [--program <path>]
+       aoc list
+       aoc answers
+
+options allowed anywhere:
+    --threads <n>   threads for solutions that can use more than one, defaults to one per core
+
+logging, to stderr, also allowed anywhere:
+    -q, --quiet     only print answers and errors
+    -v              also print debug output from the solutions
+    -vv             also print per-step trace output
+    --log <level>   one of quiet, info (the default), debug or trace
+
+run options:
+    --day <n>       puzzle day to run
+    --part <n>      puzzle part to run
+    --input <path>  puzzle input, \"-\" for stdin, defaults to input/day<n>_input.txt
+    --accept        accept this answer as the correct one for this input
+    --cached        print the accepted answer for this input instead of solving it,
+                    solving it anyway if there isn't one
+
+bench options:
+    --day <n>              only time this day, can be repeated
+    --runs <n>             runs per part, the fastest is kept, defaults to 5
+    --threshold <percent>  slowdown that counts as a regression, defaults to 20
+    --personal             time input/day<n>_input.txt instead of the examples
+    --save-baseline        save these timings as the baseline to compare against
+    --snailfish            compare both representations of snailfish numbers on day 18
+                           part 2 instead
+
+render options, for the days that work on a grid (9, 11, 13, 15, 20 and 25):
+    --day <n>          puzzle day to render
+    --steps <n>        how many steps, folds or enhancements to run first, defaults to 0
+    --format <format>  one of ascii (the default), ansi, pbm or pgm
+    --input <path>     puzzle input, \"-\" for stdin, defaults to input/day<n>_input.txt
+
+animate options, for the days that simulate a grid (11, 20 and 25):
+    --day <n>          puzzle day to animate
+    --steps <n>        how many generations to record, defaults to however many the puzzle needs
+    --fps <n>          frames per second to play back at, defaults to 10
+    --format <format>  one of ascii (the default), ansi, pbm or pgm, images need --export
+    --export <dir>     write each frame to <dir>/frame_<generation>.<ext> instead of playing them
+    --input <path>     puzzle input, \"-\" for stdin, defaults to input/day<n>_input.txt
+
+export options, for writing out the aligned beacons and scanners of day 19, or the lit
+cuboids of day 22 as steps that reboot to the same state:
+    --day <n>          puzzle day to export, 19 or 22
+    --format <format>  for day 19, one of ply (the default) or xyz
+    --input <path>     puzzle input, \"-\" for stdin, defaults to input/day<n>_input.txt
+
+query options, for asking about the state of the reactor after day 22's reboot:
+    --day <n>          puzzle day to query, only 22 for now
+    --point <x,y,z>    whether this cube is on and which step last touched it, can be repeated
+    --region <ranges>  how many cubes are on inside e.g. x=-5..5,y=0..3,z=1..1, can be repeated
+    --input <path>     puzzle input, \"-\" for stdin, defaults to input/day<n>_input.txt
+
+step options, for going through day 22's reboot one step at a time:
+    --day <n>          puzzle day to step through, only 22 for now
+    --input <path>     puzzle input, defaults to input/day<n>_input.txt
+
+step reads commands from stdin, one per line:
+    next [n]           apply the next n steps, defaults to 1, printing the lit volume after each
+    undo [n]           take back the last n steps, defaults to 1
+    point <x,y,z>      whether this cube is on and which step last touched it
+    region <ranges>    how many cubes are on inside the region
+    quit               stop, as does the end of stdin
+
+alu options, for running a program for day 24's ALU, with -vv tracing every instruction:
+    --inputs <n,n,...>  values for inp to read, in order
+    --digits <digits>   values for inp to read, one digit each, e.g. a model number
+    --program <path>    the program, \"-\" for stdin, defaults to input/day24_input.txt";
+
+// every run is recorded here, along with the answers we've accepted
+const ANSWERS_PATH: &str = "input/verified_answers.tsv";
+
+const BASELINE_PATH: &str = "input/bench_baseline.tsv";
+
+struct RunArgs {
+    day: u32,
+    part: u32,
+    input: PathBuf,
+    accept: bool,
+    cached: bool,
+}
+
+struct RenderArgs {
+    day: u32,
+    steps: u32,
+    format: Format,
+    input: PathBuf,
+}
+
+struct AnimateArgs {
+    day: u32,
+    steps: Option<u32>,
+    fps: u32,
+    format: Format,
+    export: Option<PathBuf>,
+    input: PathBuf,
+}
+
+struct ExportArgs {
+    day: u32,
+    format: Option<CloudFormat>,
+    input: PathBuf,
+}
+
+struct AluArgs {
+    inputs: Vec<i64>,
+    program: PathBuf,
+}
+
+struct StepArgs {
+    day: u32,
+    input: PathBuf,
+}
+
+struct QueryArgs {
+    day: u32,
+    points: Vec<[i64; 3]>,
+    regions: Vec<Cube>,
+    input: PathBuf,
+}
+
+struct BenchArgs {
+    days: Vec<u32>,
+    runs: u32,
+    // percent, 20 unless given
+    threshold: Option<u32>,
+    personal: bool,
+    save_baseline: bool,
+    snailfish: bool,
+}
+
+pub fn run<I>(args: I) -> Result<(), String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = take_global_flags(args.into_iter())?.into_iter();
+    match args.next().as_deref() {
+        Some("run") => run_day(parse_run_args(args)?),
+        Some("bench") => run_bench(parse_bench_args(args)?),
+        Some("render") => render_day(parse_render_args(args)?),
+        Some("animate") => animate_day(parse_animate_args(args)?),
+        Some("export") => export_day(parse_export_args(args)?),
+        Some("query") => query_day(parse_query_args(args)?),
+        Some("step") => step_day(parse_step_args(args)?),
+        Some("alu") => run_alu(parse_alu_args(args)?),
+        Some("list") => {
+            list();
+            Ok(())
+        }
+        Some("answers") => list_answers(),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(cmd) => Err(format!("unknown command: {}\n\n{}", cmd, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+// handles the options that apply to every command, wherever they are, and returns the
+// rest of the arguments
+fn take_global_flags<I>(mut args: I) -> Result<Vec<String>, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut rest = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-q" | "--quiet" => log::set_level(Level::Quiet),
+            "-v" => log::set_level(Level::Debug),
+            "-vv" => log::set_level(Level::Trace),
+            "--threads" => {
+                let threads = args.next().ok_or("missing value for --threads")?;
+                let threads = parse_number("--threads", &threads)?;
+                if threads == 0 {
+                    return Err("--threads must be at least 1".to_string());
+                }
+                parallel::set_threads(threads as usize);
+            }
+            "--log" => {
+                let level = args.next().ok_or("missing value for --log")?;
+                log::set_level(level.parse()?);
+            }
+            _ => rest.push(arg),
+        }
+    }
+    Ok(rest)
+}
+
+fn parse_run_args<I>(mut args: I) -> Result<RunArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut accept = false;
+    let mut cached = false;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--part" => part = Some(parse_number(&flag, &value()?)?),
+            "--input" => input = Some(PathBuf::from(value()?)),
+            "--accept" => accept = true,
+            "--cached" => cached = true,
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    let part = part.ok_or_else(|| format!("missing --part\n\n{}", USAGE))?;
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(RunArgs {
+        day,
+        part,
+        input,
+        accept,
+        cached,
+    })
+}
+
+fn parse_bench_args<I>(mut args: I) -> Result<BenchArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut bench_args = BenchArgs {
+        days: Vec::new(),
+        runs: 5,
+        threshold: None,
+        personal: false,
+        save_baseline: false,
+        snailfish: false,
+    };
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => bench_args.days.push(parse_number(&flag, &value()?)?),
+            "--runs" => bench_args.runs = parse_number(&flag, &value()?)?,
+            "--threshold" => bench_args.threshold = Some(parse_number(&flag, &value()?)?),
+            "--personal" => bench_args.personal = true,
+            "--save-baseline" => bench_args.save_baseline = true,
+            "--snailfish" => bench_args.snailfish = true,
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    if bench_args.snailfish
+        && (!bench_args.days.is_empty()
+            || bench_args.threshold.is_some()
+            || bench_args.save_baseline)
+    {
+        return Err(
+            "--snailfish can't be combined with --day, --threshold or --save-baseline".to_string(),
+        );
+    }
+    Ok(bench_args)
+}
+
+fn parse_render_args<I>(mut args: I) -> Result<RenderArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut steps = 0;
+    let mut format = Format::Ascii;
+    let mut input = None;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--steps" => steps = parse_number(&flag, &value()?)?,
+            "--format" => format = value()?.parse()?,
+            "--input" => input = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(RenderArgs {
+        day,
+        steps,
+        format,
+        input,
+    })
+}
+
+fn parse_animate_args<I>(mut args: I) -> Result<AnimateArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut steps = None;
+    let mut fps = 10;
+    let mut format = Format::Ascii;
+    let mut export = None;
+    let mut input = None;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--steps" => steps = Some(parse_number(&flag, &value()?)?),
+            "--fps" => fps = parse_number(&flag, &value()?)?,
+            "--format" => format = value()?.parse()?,
+            "--export" => export = Some(PathBuf::from(value()?)),
+            "--input" => input = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    if fps == 0 {
+        return Err("--fps must be at least 1".to_string());
+    }
+    if export.is_none() && !format.is_text() {
+        return Err("only ascii and ansi can be played back, use --export for images".to_string());
+    }
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(AnimateArgs {
+        day,
+        steps,
+        fps,
+        format,
+        export,
+        input,
+    })
+}
+
+fn parse_export_args<I>(mut args: I) -> Result<ExportArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut format = None;
+    let mut input = None;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--format" => format = Some(value()?.parse()?),
+            "--input" => input = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(ExportArgs { day, format, input })
+}
+
+fn parse_query_args<I>(mut args: I) -> Result<QueryArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut points = Vec::new();
+    let mut regions = Vec::new();
+    let mut input = None;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--point" => points.push(parse_point(&flag, &value()?)?),
+            "--region" => {
+                regions.push(day22::parse_region(&value()?).map_err(|err| err.to_string())?)
+            }
+            "--input" => input = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(QueryArgs {
+        day,
+        points,
+        regions,
+        input,
+    })
+}
+
+fn parse_step_args<I>(mut args: I) -> Result<StepArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut input = None;
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        match flag.as_str() {
+            "--day" => day = Some(parse_number(&flag, &value()?)?),
+            "--input" => input = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let day = day.ok_or_else(|| format!("missing --day\n\n{}", USAGE))?;
+    let input = input.unwrap_or_else(|| PathBuf::from(format!("input/day{}_input.txt", day)));
+    Ok(StepArgs { day, input })
+}
+
+fn parse_alu_args<I>(mut args: I) -> Result<AluArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut inputs = Vec::new();
+    let mut program = PathBuf::from("input/day24_input.txt");
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+        let invalid = |value: &str| format!("invalid value for {}: {}", flag, value);
+        match flag.as_str() {
+            "--inputs" => {
+                let values = value()?;
+                for input in values.split(',') {
+                    inputs.push(input.trim().parse().map_err(|_| invalid(&values))?);
+                }
+            }
+            "--digits" => {
+                let digits = value()?;
+                for digit in digits.chars() {
+                    inputs.push(digit.to_digit(10).ok_or_else(|| invalid(&digits))? as i64);
+                }
+            }
+            "--program" => program = PathBuf::from(value()?),
+            _ => return Err(format!("unknown option: {}\n\n{}", flag, USAGE)),
+        }
+    }
+    Ok(AluArgs { inputs, program })
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+fn parse_point(flag: &str, value: &str) -> Result<[i64; 3], String> {
+    let coords = value
+        .split(',')
+        .map(|coord| coord.trim().parse().ok())
+        .collect::<Option<Vec<i64>>>();
+    match coords.as_deref() {
+        Some(&[x, y, z]) => Ok([x, y, z]),
+        _ => Err(format!("invalid value for {}: {}", flag, value)),
+    }
+}
+
+fn run_day(args: RunArgs) -> Result<(), String> {
+    let RunArgs {
+        day,
+        part,
+        input,
+        accept,
+        cached,
+    } = args;
+    let solution = solution::lookup(day).ok_or_else(|| format!("no solution for day {}", day))?;
+    let text = input::read_all(&input).map_err(|err| err.to_string())?;
+    let hash = store::hash(text.as_bytes());
+    let mut answers = Answers::load(ANSWERS_PATH).map_err(|err| err.to_string())?;
+
+    if cached {
+        if let Some(accepted) = answers
+            .get(day, part, hash)
+            .and_then(|entry| entry.accepted.as_ref())
+        {
+            println!("{}", accepted);
+            return Ok(());
+        }
+        info!("no accepted answer for day {} part {}; solving", day, part);
+    }
+
+    let lines: AnyLines = Lines::new(&input::source_name(&input), Box::new(Cursor::new(text)));
+    let start = Instant::now();
+    let answer = solution.run(lines, part).map_err(|err| err.to_string())?;
+    let elapsed = start.elapsed();
+    println!("{}", answer);
+    info!("day {} part {}: took {:?}", day, part, elapsed);
+
+    let verdict = answers.record(day, part, hash, &answer, elapsed);
+    if accept {
+        answers.accept(day, part, hash, &answer);
+    }
+    answers.save().map_err(|err| err.to_string())?;
+    match verdict {
+        Verdict::Wrong(accepted) if !accept => Err(format!(
+            "day {} part {}: answer differs from the accepted answer {:?}",
+            day, part, accepted
+        )),
+        Verdict::Unverified if !accept => {
+            info!(
+                "day {} part {}: no accepted answer for this input yet, rerun with --accept once it's right",
+                day, part
+            );
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// prints a tab separated report, and fails if anything got slower than the baseline
+fn run_bench(args: BenchArgs) -> Result<(), String> {
+    if args.snailfish {
+        return run_snailfish_bench(args);
+    }
+    let threshold = args.threshold.unwrap_or(20);
+    let input_path = if args.personal {
+        |day| PathBuf::from(format!("input/day{}_input.txt", day))
+    } else {
+        |day| PathBuf::from(format!("input/examples/day{}.txt", day))
+    };
+    let timings =
+        bench::time_days(&args.days, args.runs, input_path).map_err(|err| err.to_string())?;
+    let mut baseline = Baseline::load(BASELINE_PATH).map_err(|err| err.to_string())?;
+
+    let mut regressions = 0;
+    println!("day\tpart\tparse_us\tsolve_us\tbaseline_parse_us\tbaseline_solve_us\tstatus");
+    for &(day, part, hash, timing) in &timings {
+        let (status, baseline_parse, baseline_solve) = match baseline.get(day, part, hash) {
+            Some(previous) => (
+                timing.compare(previous, threshold as f64 / 100.0),
+                previous.parse.as_micros().to_string(),
+                previous.solve.as_micros().to_string(),
+            ),
+            None => (Status::New, "-".to_string(), "-".to_string()),
+        };
+        if let Status::Regressed = status {
+            regressions += 1;
+        }
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            day,
+            part,
+            timing.parse.as_micros(),
+            timing.solve.as_micros(),
+            baseline_parse,
+            baseline_solve,
+            status.name()
+        );
+    }
+
+    if args.save_baseline {
+        for &(day, part, hash, timing) in &timings {
+            baseline.update(day, part, hash, timing);
+        }
+        baseline.save().map_err(|err| err.to_string())?;
+    }
+    match regressions {
+        0 => Ok(()),
+        n => Err(format!("{} parts regressed by more than {}%", n, threshold)),
+    }
+}
+
+// prints the grid a day works on, as it is after some number of steps
+fn render_day(args: RenderArgs) -> Result<(), String> {
+    let RenderArgs {
+        day,
+        steps,
+        format,
+        input,
+    } = args;
+    if ![9, 11, 13, 15, 20, 25].contains(&day) {
+        return Err(format!("day {} doesn't have a grid to render", day));
+    }
+    let lines = input::open(&input).map_err(|err| err.to_string())?;
+    let rendered = match day {
+        9 | 15 if steps > 0 => return Err(format!("day {} is a map, it has no steps", day)),
+        9 => Day9.parse(lines).map(|heights| format.render(&heights)),
+        11 => day11::energy_after(lines, steps).map(|energy| format.render(&energy)),
+        13 => day13::paper_after(lines, steps as usize).map(|paper| format.render(&paper)),
+        15 => Day15.parse(lines).map(|risks| format.render(&risks)),
+        20 => day20::lights_after(lines, steps as usize).map(|lights| format.render(&lights)),
+        25 => day25::herds_after(lines, steps as usize).map(|herds| format.render(&herds)),
+        _ => unreachable!(),
+    }
+    .map_err(|err| err.to_string())?;
+    println!("{}", rendered.trim_end_matches('\n'));
+    Ok(())
+}
+
+// records every generation of a day's grid, then plays them back or writes them out
+fn animate_day(args: AnimateArgs) -> Result<(), String> {
+    let AnimateArgs {
+        day,
+        steps,
+        fps,
+        format,
+        export,
+        input,
+    } = args;
+    if ![11, 20, 25].contains(&day) {
+        return Err(format!("day {} doesn't have a simulation to animate", day));
+    }
+    let lines = input::open(&input).map_err(|err| err.to_string())?;
+    let limit = steps.map(|steps| steps as usize);
+    let frames: Vec<String> = match day {
+        11 => day11::generations(lines, limit).map(|frames| render_all(&frames, format)),
+        20 => day20::generations(lines, limit).map(|frames| render_all(&frames, format)),
+        25 => day25::generations(lines, limit).map(|frames| render_all(&frames, format)),
+        _ => unreachable!(),
+    }
+    .map_err(|err| err.to_string())?;
+
+    match export {
+        Some(dir) => {
+            animate::export(&frames, &dir, format.extension()).map_err(|err| err.to_string())?;
+            info!("wrote {} frames to {}", frames.len(), dir.display());
+            Ok(())
+        }
+        None => animate::play(&frames, Duration::from_secs(1) / fps).map_err(|err| err.to_string()),
+    }
+}
+
+fn render_all<T>(frames: &[Grid<T>], format: Format) -> Vec<String>
+where
+    T: Shade,
+{
+    frames.iter().map(|frame| format.render(frame)).collect()
+}
+
+fn run_snailfish_bench(args: BenchArgs) -> Result<(), String> {
+    let path = if args.personal {
+        PathBuf::from("input/day18_input.txt")
+    } else {
+        PathBuf::from("input/examples/day18.txt")
+    };
+    let timings = bench::time_snailfish(&path, args.runs).map_err(|err| err.to_string())?;
+    println!("representation\tparse_us\tsolve_us\tanswer");
+    for (name, timing, answer) in &timings {
+        println!(
+            "{}\t{}\t{}\t{}",
+            name,
+            timing.parse.as_micros(),
+            timing.solve.as_micros(),
+            answer
+        );
+    }
+    if let [(_, tree, tree_answer), (_, flat, flat_answer)] = &timings[..] {
+        if tree_answer != flat_answer {
+            return Err("the representations disagree on the answer".to_string());
+        }
+        info!(
+            "flat is {:.1}x as fast as tree",
+            tree.total().as_secs_f64() / flat.total().as_secs_f64()
+        );
+    }
+    Ok(())
+}
+
+// prints a day's results in a format other tools can open
+fn export_day(args: ExportArgs) -> Result<(), String> {
+    let lines = input::open(&args.input).map_err(|err| err.to_string())?;
+    let exported = match (args.day, args.format) {
+        (19, format) => day19::point_cloud(lines, format.unwrap_or(CloudFormat::Ply)),
+        (22, None) => day22::reactor(lines).map(|reactor| reactor.decomposition()),
+        (22, Some(_)) => return Err("day 22 only exports one format".to_string()),
+        (day, _) => return Err(format!("day {} doesn't have anything to export", day)),
+    };
+    print!("{}", exported.map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+fn query_day(args: QueryArgs) -> Result<(), String> {
+    if args.day != 22 {
+        return Err(format!("day {} doesn't have anything to query", args.day));
+    }
+    let lines = input::open(&args.input).map_err(|err| err.to_string())?;
+    let reactor = day22::reactor(lines).map_err(|err| err.to_string())?;
+    info!(
+        "{} cubes are on, in {} disjoint cuboids",
+        reactor.lit_volume(),
+        reactor.lit_cuboids().len()
+    );
+    for point in args.points {
+        print_point(&reactor, point);
+    }
+    for region in args.regions {
+        println!("{}: {} on", region, reactor.lit_within(&region));
+    }
+    Ok(())
+}
+
+fn print_point(reactor: &Reactor, point: [i64; 3]) {
+    let [x, y, z] = point;
+    let state = if reactor.is_on(point) { "on" } else { "off" };
+    match reactor.last_touched(point) {
+        Some(step) => println!(
+            "{},{},{}: {}, last touched by {}",
+            x,
+            y,
+            z,
+            state,
+            describe_step(reactor, step)
+        ),
+        None => println!("{},{},{}: {}, never touched", x, y, z, state),
+    }
+}
+
+// steps are numbered from 1, like the lines of the input
+fn describe_step(reactor: &Reactor, step: usize) -> String {
+    let (is_on, cuboid) = reactor.steps()[step];
+    let action = if is_on { "on" } else { "off" };
+    format!("step {} ({} {})", step + 1, action, cuboid)
+}
+
+// an interactive reboot, driven by commands on stdin
+fn step_day(args: StepArgs) -> Result<(), String> {
+    if args.day != 22 {
+        return Err(format!("day {} can't be stepped through", args.day));
+    }
+    if args.input == Path::new("-") {
+        return Err("step reads its commands from stdin, so needs --input <path>".to_string());
+    }
+    let lines = input::open(&args.input).map_err(|err| err.to_string())?;
+    let mut reactor = Reactor::new(day22::parse_steps(lines).map_err(|err| err.to_string())?);
+    info!("{} steps to apply", reactor.steps().len());
+    for command in io::stdin().lock().lines() {
+        let command = command.map_err(|err| err.to_string())?;
+        let (name, value) = match command.trim().split_once(' ') {
+            Some((name, value)) => (name, value.trim()),
+            None => (command.trim(), ""),
+        };
+        if name == "quit" || name == "q" {
+            break;
+        }
+        // a typo shouldn't throw away the steps applied so far
+        if let Err(err) = step_command(&mut reactor, name, value) {
+            println!("{}", err);
+        }
+    }
+    Ok(())
+}
+
+fn step_command(reactor: &mut Reactor, name: &str, value: &str) -> Result<(), String> {
+    let count = || match value {
+        "" => Ok(1),
+        value => parse_number(name, value),
+    };
+    match name {
+        "next" | "n" => {
+            for _ in 0..count()? {
+                let before = reactor.lit_volume();
+                let step = reactor.applied();
+                match reactor.apply() {
+                    Some(after) => println!(
+                        "{}: {} on ({:+})",
+                        describe_step(reactor, step),
+                        after,
+                        after as i128 - before as i128
+                    ),
+                    None => {
+                        println!("all {} steps are applied", step);
+                        break;
+                    }
+                }
+            }
+        }
+        "undo" | "u" => {
+            for _ in 0..count()? {
+                match reactor.undo() {
+                    Some(after) => println!(
+                        "undid {}: {} on",
+                        describe_step(reactor, reactor.applied()),
+                        after
+                    ),
+                    None => {
+                        println!("no steps are applied");
+                        break;
+                    }
+                }
+            }
+        }
+        "point" | "p" => print_point(reactor, parse_point(name, value)?),
+        "region" | "r" => {
+            let region = day22::parse_region(value).map_err(|err| err.to_string())?;
+            println!("{}: {} on", region, reactor.lit_within(&region));
+        }
+        "" => {}
+        _ => return Err(format!("unknown command: {}", name)),
+    }
+    Ok(())
+}
+
+fn run_alu(args: AluArgs) -> Result<(), String> {
+    let lines = input::open(&args.program).map_err(|err| err.to_string())?;
+    let program = alu::parse_program(lines).map_err(|err| err.to_string())?;
+    debug!("{} instructions", program.len());
+    match alu::run(&program, args.inputs) {
+        Ok(registers) => {
+            println!("{}", registers);
+            Ok(())
+        }
+        Err(err) => {
+            let index = match err {
+                AluError::InputExhausted { instruction }
+                | AluError::DivideByZero { instruction }
+                | AluError::InvalidMod { instruction, .. }
+                | AluError::Overflow { instruction } => instruction,
+            };
+            Err(format!("{} ({})", err, program[index]))
+        }
+    }
+}
+
+fn list() {
+    for (day, solution) in solution::registry() {
+        let parts = (1..=solution.parts()).map(|part| part.to_string());
+        println!(
+            "day {}: parts {}",
+            day,
+            parts.collect::<Vec<_>>().join(", ")
+        );
+    }
+}
+
+fn list_answers() -> Result<(), String> {
+    let answers = Answers::load(ANSWERS_PATH).map_err(|err| err.to_string())?;
+    for (day, part, hash, entry) in answers.entries() {
+        let status = match &entry.accepted {
+            Some(accepted) if *accepted == entry.last => "accepted",
+            Some(_) => "wrong",
+            None => "unverified",
+        };
+        println!(
+            "day {} part {} input {:016x}: {:?} ({}, took {:?})",
+            day, part, hash, entry.last, status, entry.elapsed
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,8 @@
-use crate::util::read_lines;
-use std::{iter::IntoIterator, path::Path};
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 fn num_higher(depths: &[i32]) -> usize {
     depths.windows(2).filter(|pair| pair[0] < pair[1]).count()
@@ -9,26 +12,43 @@ fn num_higher_3_wide(depths: &[i32]) -> usize {
     depths.windows(4).filter(|pair| pair[0] < pair[3]).count()
 }
 
-pub fn solution_1<P>(fname: P) -> usize
+fn parse_depths<I>(input: I) -> Result<Vec<i32>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(fname).expect("failed to open");
-    let depths: Vec<i32> = lines
-        .into_iter()
-        .map(|f| f.expect("not a line").parse().expect("not a num"))
-        .collect();
-    num_higher(&depths)
+    input.into_lines()?.map(|line| line?.parse()).collect()
 }
 
-pub fn solution_2<P>(fname: P) -> usize
+pub fn solution_1<I>(input: I) -> Result<usize>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(fname).expect("failed to open");
-    let depths: Vec<i32> = lines
-        .into_iter()
-        .map(|f| f.expect("not a line").parse().expect("not a num"))
-        .collect();
-    num_higher_3_wide(&depths)
+    Ok(num_higher(&parse_depths(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(num_higher_3_wide(&parse_depths(input)?))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Puzzle = Vec<i32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<i32>> {
+        parse_depths(input)
+    }
+
+    fn part_1(&self, depths: &Vec<i32>) -> Result<usize> {
+        Ok(num_higher(depths))
+    }
+
+    fn part_2(&self, depths: &Vec<i32>) -> Result<usize> {
+        Ok(num_higher_3_wide(depths))
+    }
 }
@@ -1,13 +1,17 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    path::Path,
-};
+use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
-fn parse_line(closing_map: &HashMap<char, char>, line: String) -> Result<VecDeque<char>, char> {
+fn parse_line(
+    closing_map: &HashMap<char, char>,
+    line: &str,
+) -> std::result::Result<VecDeque<char>, char> {
     line.chars()
         .try_fold(VecDeque::<char>::new(), |mut stack, c| match c {
             '(' | '[' | '{' | '<' => {
@@ -15,33 +19,42 @@ fn parse_line(closing_map: &HashMap<char, char>, line: String) -> Result<VecDequ
                 Ok(stack)
             }
             ')' | ']' | '}' | '>' => {
-                if let Some(stack_c) = stack.pop_back() {
-                    if c == *closing_map.get(&stack_c).unwrap() {
-                        Ok(stack)
-                    } else {
-                        Err(c)
-                    }
-                } else {
-                    unreachable!()
+                // closing with nothing open is as corrupted as closing the wrong chunk
+                match stack.pop_back() {
+                    Some(stack_c) if c == closing_map[&stack_c] => Ok(stack),
+                    _ => Err(c),
                 }
             }
+            // parse_chunks only lets bracket characters through
             _ => unreachable!(),
         })
 }
 
-pub fn solution_1<P>(filename: P) -> u32
+fn parse_chunks<I>(input: I) -> Result<Vec<String>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
+    input
+        .into_lines()?
+        .map(|line| {
+            let line = line?;
+            match line.chars().find(|c| !"()[]{}<>".contains(*c)) {
+                Some(c) => Err(line.error(format!("unexpected character {:?}", c))),
+                None => Ok(line.to_string()),
+            }
+        })
+        .collect()
+}
+
+fn syntax_error_score(lines: &[String]) -> u32 {
     let point_map: HashMap<char, u32> =
         HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
     let closing_map: HashMap<char, char> =
         HashMap::from([('(', ')'), ('{', '}'), ('[', ']'), ('<', '>')]);
 
-    let lines = read_lines(filename).expect("failed to read input");
     lines
-        .into_iter()
-        .map(|line| parse_line(&closing_map, line.unwrap()))
+        .iter()
+        .map(|line| parse_line(&closing_map, line))
         .map(|res| {
             if let Err(c) = res {
                 *point_map.get(&c).unwrap()
@@ -52,18 +65,14 @@ where
         .sum()
 }
 
-pub fn solution_2<P>(filename: P) -> u64
-where
-    P: AsRef<Path>,
-{
+fn completion_score(lines: &[String]) -> Result<u64> {
     let point_map: HashMap<char, u64> = HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]);
     let closing_map: HashMap<char, char> =
         HashMap::from([('(', ')'), ('{', '}'), ('[', ']'), ('<', '>')]);
 
-    let lines = read_lines(filename).expect("failed to read input");
     let scores: Vec<u64> = lines
-        .into_iter()
-        .filter_map(|line| parse_line(&closing_map, line.unwrap()).ok())
+        .iter()
+        .filter_map(|line| parse_line(&closing_map, line).ok())
         .map(|res| {
             res.into_iter().rev().fold(0u64, |acc, c| {
                 acc * 5 + point_map.get(closing_map.get(&c).unwrap()).unwrap()
@@ -71,5 +80,42 @@ where
         })
         .sorted()
         .collect();
-    scores[scores.len() / 2]
+    if scores.is_empty() {
+        return Err(Error::NoSolution("every line is corrupted".to_string()));
+    }
+    Ok(scores[scores.len() / 2])
+}
+
+pub fn solution_1<I>(input: I) -> Result<u32>
+where
+    I: Input,
+{
+    Ok(syntax_error_score(&parse_chunks(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    completion_score(&parse_chunks(input)?)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Puzzle = Vec<String>;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<String>> {
+        parse_chunks(input)
+    }
+
+    fn part_1(&self, lines: &Vec<String>) -> Result<u32> {
+        Ok(syntax_error_score(lines))
+    }
+
+    fn part_2(&self, lines: &Vec<String>) -> Result<u64> {
+        completion_score(lines)
+    }
 }
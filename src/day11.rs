@@ -1,97 +1,45 @@
-use std::{
-    collections::{HashMap, HashSet},
-    path::Path,
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+    util::{parse_digits, Coord, Grid},
 };
 
-use crate::util::read_lines;
-
-const SURROUND: [(i32, i32); 8] = [
-    (1, 1),
-    (0, 1),
-    (-1, 1),
-    (1, 0),
-    (-1, 0),
-    (1, -1),
-    (0, -1),
-    (-1, -1),
-];
-
-fn step(grid: &mut HashMap<(i32, i32), u32>) -> usize {
-    let mut has_flashed: HashSet<(i32, i32)> = HashSet::new();
-    grid.clone().keys().into_iter().for_each(|coord| {
-        *(grid.get_mut(coord).unwrap()) += 1;
-    });
+fn step(grid: &mut Grid<u32>) -> usize {
+    let mut has_flashed: Grid<bool> = Grid::new(grid.rows(), grid.cols(), false);
+    grid.values_mut().for_each(|level| *level += 1);
     loop {
-        let new_flashes: Vec<(i32, i32)> = grid
+        let new_flashes: Vec<Coord> = grid
             .iter()
-            .filter_map(|(coord, level)| {
-                if *level > 9 && !has_flashed.contains(coord) {
-                    Some(*coord)
-                } else {
-                    None
-                }
-            })
+            .filter(|&(coord, level)| *level > 9 && !has_flashed[coord])
+            .map(|(coord, _)| coord)
             .collect();
         if new_flashes.is_empty() {
             break;
         }
-        has_flashed.extend(new_flashes.iter());
 
-        new_flashes.iter().for_each(|(x, y)| {
-            SURROUND.iter().for_each(|(offx, offy)| {
-                if let Some(entry) = grid.get_mut(&(x + offx, y + offy)) {
-                    *entry += 1;
-                }
-            })
-        })
+        for coord in new_flashes {
+            has_flashed[coord] = true;
+            for neighbor in grid.surrounding(coord).collect::<Vec<_>>() {
+                grid[neighbor] += 1;
+            }
+        }
     }
-    grid.clone().into_iter().for_each(|(coord, level)| {
-        *(grid.get_mut(&coord).unwrap()) = if level > 9 { 0 } else { level }
+    grid.values_mut().for_each(|level| {
+        if *level > 9 {
+            *level = 0
+        }
     });
-    has_flashed.len()
+    has_flashed.values().filter(|flashed| **flashed).count()
 }
 
-pub fn solution_1<P>(filename: P, steps: u32) -> usize
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-
-    let mut vals: HashMap<(i32, i32), u32> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row, line)| {
-            line.unwrap()
-                .chars()
-                .enumerate()
-                .map(|(col, c)| ((row as i32, col as i32), c.to_digit(10).unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-
+fn count_flashes(grid: &Grid<u32>, steps: u32) -> usize {
+    let mut vals = grid.clone();
     (0..steps).fold(0usize, |flashes, _| flashes + step(&mut vals))
 }
 
-pub fn solution_2<P>(filename: P) -> usize
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-
-    let mut vals: HashMap<(i32, i32), u32> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row, line)| {
-            line.unwrap()
-                .chars()
-                .enumerate()
-                .map(|(col, c)| ((row as i32, col as i32), c.to_digit(10).unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-
+fn first_synchronized_step(grid: &Grid<u32>) -> usize {
+    let mut vals = grid.clone();
     let mut my_step = 0;
     loop {
         my_step += 1;
@@ -101,3 +49,67 @@ where
     }
     my_step
 }
+
+// the energy levels after `steps` steps, for rendering
+pub fn energy_after<I>(input: I, steps: u32) -> Result<Grid<u32>>
+where
+    I: Input,
+{
+    let mut grid = parse_digits(input)?;
+    for _ in 0..steps {
+        step(&mut grid);
+    }
+    Ok(grid)
+}
+
+// every generation up to `limit` steps, or up to the first synchronized flash if there's
+// no limit, for animating
+pub fn generations<I>(input: I, limit: Option<usize>) -> Result<Vec<Grid<u32>>>
+where
+    I: Input,
+{
+    let mut grid = parse_digits(input)?;
+    let mut frames = vec![grid.clone()];
+    while limit.is_none_or(|limit| frames.len() <= limit) {
+        let flashes = step(&mut grid);
+        frames.push(grid.clone());
+        if limit.is_none() && flashes == grid.len() {
+            break;
+        }
+    }
+    Ok(frames)
+}
+
+pub fn solution_1<I>(input: I, steps: u32) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(count_flashes(&parse_digits(input)?, steps))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(first_synchronized_step(&parse_digits(input)?))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Puzzle = Grid<u32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Grid<u32>> {
+        parse_digits(input)
+    }
+
+    fn part_1(&self, grid: &Grid<u32>) -> Result<usize> {
+        Ok(count_flashes(grid, 100))
+    }
+
+    fn part_2(&self, grid: &Grid<u32>) -> Result<usize> {
+        Ok(first_synchronized_step(grid))
+    }
+}
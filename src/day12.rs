@@ -1,8 +1,9 @@
-use crate::util::read_lines;
-use std::{
-    collections::{HashMap, HashSet},
-    path::Path,
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
 };
+use std::collections::{HashMap, HashSet};
 
 fn is_small(s: &str) -> bool {
     s.chars().next().unwrap().is_lowercase()
@@ -32,30 +33,40 @@ fn count_paths(
     count
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+fn parse_edges<I>(input: I) -> Result<HashMap<String, Vec<String>>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let edges: Vec<(String, String)> = lines
-        .into_iter()
-        .map(|line| {
-            let unwrapped = line.unwrap();
-            let mut edge_str = unwrapped.split('-');
-            let (a, b) = (edge_str.next().unwrap(), edge_str.next().unwrap());
-            [
-                (a.to_string(), b.to_string()),
-                (b.to_string(), a.to_string()),
-            ]
-        })
-        .flatten()
-        .collect();
+    let mut lines = input.into_lines()?;
     let mut edge_map: HashMap<String, Vec<String>> = HashMap::new();
-    for (a, b) in edges {
-        (edge_map.entry(a.to_string()).or_insert_with(Vec::new)).push(b.to_string());
+    for line in &mut lines {
+        let line = line?;
+        let (a, b) = match line.split('-').collect::<Vec<_>>()[..] {
+            [a, b] if !a.is_empty() && !b.is_empty() => (a, b),
+            _ => return Err(line.error("expected an edge like \"a-b\"")),
+        };
+        edge_map
+            .entry(a.to_string())
+            .or_default()
+            .push(b.to_string());
+        edge_map
+            .entry(b.to_string())
+            .or_default()
+            .push(a.to_string());
     }
+    if !edge_map.contains_key("start") {
+        return Err(lines.error("no edges from \"start\""));
+    }
+    Ok(edge_map)
+}
+
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    let edge_map = parse_edges(input)?;
     let mut visited: HashSet<String> = HashSet::new();
-    count_paths(&edge_map, &mut visited, "start")
+    Ok(count_paths(&edge_map, &mut visited, "start"))
 }
 
 fn count_paths_double(
@@ -93,28 +104,36 @@ fn count_paths_double(
     count
 }
 
-pub fn solution_2<P>(filename: P) -> usize
+pub fn solution_2<I>(input: I) -> Result<usize>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let edges: Vec<(String, String)> = lines
-        .into_iter()
-        .map(|line| {
-            let unwrapped = line.unwrap();
-            let mut edge_str = unwrapped.split('-');
-            let (a, b) = (edge_str.next().unwrap(), edge_str.next().unwrap());
-            [
-                (a.to_string(), b.to_string()),
-                (b.to_string(), a.to_string()),
-            ]
-        })
-        .flatten()
-        .collect();
-    let mut edge_map: HashMap<String, Vec<String>> = HashMap::new();
-    for (a, b) in edges {
-        (edge_map.entry(a.to_string()).or_insert_with(Vec::new)).push(b.to_string());
-    }
+    let edge_map = parse_edges(input)?;
     let mut visited: HashSet<String> = HashSet::new();
-    count_paths_double(&edge_map, &mut visited, true, "start")
+    Ok(count_paths_double(&edge_map, &mut visited, true, "start"))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Puzzle = HashMap<String, Vec<String>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<HashMap<String, Vec<String>>> {
+        parse_edges(input)
+    }
+
+    fn part_1(&self, edge_map: &HashMap<String, Vec<String>>) -> Result<usize> {
+        Ok(count_paths(edge_map, &mut HashSet::new(), "start"))
+    }
+
+    fn part_2(&self, edge_map: &HashMap<String, Vec<String>>) -> Result<usize> {
+        Ok(count_paths_double(
+            edge_map,
+            &mut HashSet::new(),
+            true,
+            "start",
+        ))
+    }
 }
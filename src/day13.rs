@@ -1,8 +1,14 @@
-use std::{collections::HashSet, path::Path};
+use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    render,
+    solution::Solution,
+    util::Grid,
+};
 
 // axis[0] = x if true else y
 fn fold_paper(paper: &HashSet<(usize, usize)>, axis: (bool, usize)) -> HashSet<(usize, usize)> {
@@ -29,77 +35,115 @@ fn fold_paper(paper: &HashSet<(usize, usize)>, axis: (bool, usize)) -> HashSet<(
         .collect()
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+// (dots, folds) where each fold is (is x axis, line)
+type Instructions = (HashSet<(usize, usize)>, Vec<(bool, usize)>);
+
+fn parse_instructions<I>(input: I) -> Result<Instructions>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
+    let mut lines = input.into_lines()?;
     let mut paper: HashSet<(usize, usize)> = HashSet::new();
     loop {
-        let line = lines.next().unwrap().unwrap();
+        let line = lines.next_line()?;
         if line.trim().is_empty() {
             break;
         }
-        let mut coords = line.split(',');
-        paper.insert((
-            coords.next().unwrap().parse().unwrap(),
-            coords.next().unwrap().parse().unwrap(),
-        ));
+        let (x, y) = line
+            .split(',')
+            .collect_tuple()
+            .ok_or_else(|| line.error("expected a dot like \"x,y\""))?;
+        paper.insert((line.parse_field(x)?, line.parse_field(y)?));
+    }
+    if paper.is_empty() {
+        return Err(lines.error("no dots"));
+    }
+
+    let folds = (&mut lines)
+        .map(|line| {
+            let line = line?;
+            let fold_along = line
+                .strip_prefix("fold along ")
+                .ok_or_else(|| line.error("expected \"fold along\""))?;
+            let (axis, pos) = fold_along
+                .split('=')
+                .collect_tuple()
+                .ok_or_else(|| line.error("expected a fold like \"x=5\""))?;
+            let orientation = match axis {
+                "x" => true,
+                "y" => false,
+                _ => return Err(line.error(format!("unknown axis {:?}", axis))),
+            };
+            Ok((orientation, line.parse_field(pos)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if folds.is_empty() {
+        return Err(lines.error("no folds"));
     }
+    Ok((paper, folds))
+}
 
-    let line = lines.next().unwrap().unwrap();
-    let fold_along = &line[11..];
-    let mut axis = fold_along.split('=');
-    let orientation: bool = match axis.next().unwrap().parse().unwrap() {
-        'x' => true,
-        'y' => false,
-        _ => unreachable!(),
-    };
-    let line: usize = axis.next().unwrap().parse().unwrap();
-    let papers = fold_paper(&paper, (orientation, line));
-    papers.len()
+fn dots_after_first_fold((paper, folds): &Instructions) -> usize {
+    fold_paper(paper, folds[0]).len()
 }
 
-fn render_coords(coords: HashSet<(usize, usize)>) -> String {
-    let max_x = *(coords.iter().map(|(x, _)| x).max().unwrap()) as usize + 1;
-    let max_y = *(coords.iter().map(|(_, y)| y).max().unwrap()) as usize + 1;
-    let mut canvas: Vec<Vec<char>> = vec![vec![' '; max_x]; max_y];
-    coords.into_iter().for_each(|(x, y)| (canvas[y])[x] = 'x');
-    canvas
-        .into_iter()
-        .map(|row| row.into_iter().collect::<String>())
-        .join("\n")
+fn fold_all((paper, folds): &Instructions) -> String {
+    let folded = folds
+        .iter()
+        .fold(paper.clone(), |paper, axis| fold_paper(&paper, *axis));
+    render::ascii(&paper_grid(&folded), |dot| if *dot { 'x' } else { ' ' })
 }
 
-pub fn solution_2<P>(filename: P) -> String
+// the paper as a grid just big enough for all of its dots
+fn paper_grid(paper: &HashSet<(usize, usize)>) -> Grid<bool> {
+    let cols = paper.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+    let rows = paper.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+    Grid::from_fn(rows, cols, |(y, x)| paper.contains(&(x, y)))
+}
+
+// the paper after the first `folds` folds, for rendering
+pub fn paper_after<I>(input: I, folds: usize) -> Result<Grid<bool>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let mut paper: HashSet<(usize, usize)> = HashSet::new();
-    loop {
-        let line = lines.next().unwrap().unwrap();
-        if line.trim().is_empty() {
-            break;
-        }
-        let mut coords = line.split(',');
-        paper.insert((
-            coords.next().unwrap().parse().unwrap(),
-            coords.next().unwrap().parse().unwrap(),
-        ));
+    let (paper, all_folds) = parse_instructions(input)?;
+    let folded = all_folds
+        .iter()
+        .take(folds)
+        .fold(paper, |paper, axis| fold_paper(&paper, *axis));
+    Ok(paper_grid(&folded))
+}
+
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(dots_after_first_fold(&parse_instructions(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<String>
+where
+    I: Input,
+{
+    Ok(fold_all(&parse_instructions(input)?))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Puzzle = Instructions;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(&self, input: AnyLines) -> Result<Instructions> {
+        parse_instructions(input)
     }
 
-    lines.for_each(|line_res| {
-        let line = line_res.unwrap();
-        let fold_along = &line[11..];
-        let mut axis = fold_along.split('=');
-        let orientation: bool = match axis.next().unwrap().parse().unwrap() {
-            'x' => true,
-            'y' => false,
-            _ => unreachable!(),
-        };
-        let pos: usize = axis.next().unwrap().parse().unwrap();
-        paper = fold_paper(&paper, (orientation, pos));
-    });
-    render_coords(paper)
+    fn part_1(&self, instructions: &Instructions) -> Result<usize> {
+        Ok(dots_after_first_fold(instructions))
+    }
+
+    fn part_2(&self, instructions: &Instructions) -> Result<String> {
+        Ok(fold_all(instructions))
+    }
 }
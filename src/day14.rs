@@ -1,8 +1,12 @@
-use std::{collections::HashMap, path::Path};
+use std::collections::HashMap;
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 fn get_counts(
     insertion_rules: &HashMap<(char, char), char>,
@@ -36,8 +40,7 @@ fn get_counts(
     }
     let mut char_counts = counts
         .into_iter()
-        .map(|((a, b), count)| vec![(a, count), (b, count)])
-        .flatten()
+        .flat_map(|((a, b), count)| vec![(a, count), (b, count)])
         .fold(HashMap::<char, usize>::new(), |mut acc, (c, count)| {
             *(acc.entry(c).or_insert(0)) += count;
             acc
@@ -52,28 +55,42 @@ fn get_counts(
         .collect()
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+// (template, insertion rules)
+type Manual = (String, HashMap<(char, char), char>);
+
+fn parse_manual<I>(input: I) -> Result<Manual>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let template = lines.next().unwrap().unwrap();
-    let _ = lines.next();
-    let insertion_rules: HashMap<(char, char), char> = lines
-        .into_iter()
+    let mut lines = input.into_lines()?;
+    let template = lines.next_line()?;
+    if template.chars().count() < 2 {
+        return Err(template.error("template needs at least two elements"));
+    }
+    let blank = lines.next_line()?;
+    if !blank.trim().is_empty() {
+        return Err(blank.error("expected a blank line after the template"));
+    }
+    let insertion_rules = lines
         .map(|line| {
-            let line_str = line.unwrap();
-            let mut rule_pair = line_str.split_terminator(" -> ");
-            let pair_str = rule_pair.next().unwrap();
-            let insert = rule_pair.next().unwrap().chars().next().unwrap();
-            let pair: (char, char) = pair_str.chars().collect_tuple().unwrap();
-            (pair, insert)
+            let line = line?;
+            let (pair, insert) = line
+                .split_terminator(" -> ")
+                .map(|side| side.chars().collect::<Vec<_>>())
+                .collect_tuple()
+                .ok_or_else(|| line.error("expected a rule like \"AB -> C\""))?;
+            match (&pair[..], &insert[..]) {
+                ([a, b], [c]) => Ok(((*a, *b), *c)),
+                _ => Err(line.error("expected a rule like \"AB -> C\"")),
+            }
         })
-        .collect();
+        .collect::<Result<HashMap<_, _>>>()?;
+    Ok((template.to_string(), insertion_rules))
+}
 
-    let mut c_iter = get_counts(&insertion_rules, &template, 10)
-        .into_iter()
-        .map(|(_, count)| count)
+fn most_minus_least((template, insertion_rules): &Manual, num_steps: usize) -> usize {
+    let mut c_iter = get_counts(insertion_rules, template, num_steps)
+        .into_values()
         .sorted();
 
     let min_count = c_iter.next().unwrap();
@@ -82,32 +99,36 @@ where
     max_count - min_count
 }
 
-pub fn solution_2<P>(filename: P) -> usize
+pub fn solution_1<I>(input: I) -> Result<usize>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let template = lines.next().unwrap().unwrap();
-    let _ = lines.next();
-    let insertion_rules: HashMap<(char, char), char> = lines
-        .into_iter()
-        .map(|line| {
-            let line_str = line.unwrap();
-            let mut rule_pair = line_str.split_terminator(" -> ");
-            let pair_str = rule_pair.next().unwrap();
-            let insert = rule_pair.next().unwrap().chars().next().unwrap();
-            let pair: (char, char) = pair_str.chars().collect_tuple().unwrap();
-            (pair, insert)
-        })
-        .collect();
+    Ok(most_minus_least(&parse_manual(input)?, 10))
+}
 
-    let mut c_iter = get_counts(&insertion_rules, &template, 40)
-        .into_iter()
-        .map(|(_, count)| count)
-        .sorted();
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(most_minus_least(&parse_manual(input)?, 40))
+}
 
-    let min_count = c_iter.next().unwrap();
-    let max_count = c_iter.last().unwrap();
+pub struct Day14;
 
-    max_count - min_count
+impl Solution for Day14 {
+    type Puzzle = Manual;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Manual> {
+        parse_manual(input)
+    }
+
+    fn part_1(&self, manual: &Manual) -> Result<usize> {
+        Ok(most_minus_least(manual, 10))
+    }
+
+    fn part_2(&self, manual: &Manual) -> Result<usize> {
+        Ok(most_minus_least(manual, 40))
+    }
 }
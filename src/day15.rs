@@ -1,18 +1,17 @@
-use std::{
-    char::from_digit,
-    cmp::{max, Ordering},
-    collections::{BinaryHeap, HashMap},
-    path::Path,
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    render::Format,
+    solution::Solution,
+    util::{parse_chars, Coord, Grid},
 };
 
-use itertools::Itertools;
-
-use crate::util::read_lines;
-
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 struct State {
     cost: u64,
-    position: (i32, i32),
+    position: Coord,
 }
 
 impl PartialOrd for State {
@@ -31,144 +30,92 @@ impl Ord for State {
     }
 }
 
-fn render_map(costs: &HashMap<(i32, i32), u64>) -> String {
-    let max_x = *(costs.iter().map(|((x, _), _)| x).max().unwrap()) as usize + 1;
-    let max_y = *(costs.iter().map(|((_, y), _)| y).max().unwrap()) as usize + 1;
-    let mut canvas: Vec<Vec<char>> = vec![vec![' '; max_x]; max_y];
-    costs.iter().for_each(|((x, y), cost)| {
-        (canvas[*x as usize])[*y as usize] = from_digit(*cost as u32, 10).unwrap()
-    });
-    canvas
-        .into_iter()
-        .map(|row| row.into_iter().collect::<String>())
-        .join("\n")
-}
-
-fn shortest_path(costs: &HashMap<(i32, i32), u64>, start: &(i32, i32), goal: &(i32, i32)) -> u64 {
+fn shortest_path(costs: &Grid<u64>, start: Coord, goal: Coord) -> Result<u64> {
     let mut heap = BinaryHeap::new();
-    let mut dist: HashMap<_, _> = costs.keys().map(|(r, c)| ((*r, *c), u64::MAX)).collect();
-    *(dist.get_mut(start).unwrap()) = 0;
+    let mut dist: Grid<u64> = Grid::new(costs.rows(), costs.cols(), u64::MAX);
+    dist[start] = 0;
 
     heap.push(State {
         cost: 0,
-        position: *start,
+        position: start,
     });
 
-    let surround: Vec<(i32, i32)> = vec![(0, 1), (0, -1), (1, 0), (-1, 0)];
     while let Some(State { cost, position }) = heap.pop() {
-        if position == *goal {
-            return cost;
+        if position == goal {
+            return Ok(cost);
         }
-        if cost > *dist.get(&position).unwrap() {
+        if cost > dist[position] {
             continue;
         }
-        let (r, c) = position;
-        surround
-            .iter()
-            .filter_map(|(offr, offc)| {
-                let next_pos = (r + offr, c + offc);
-                costs.get(&next_pos).map(|next_cost| State {
-                    cost: cost + *next_cost,
-                    position: next_pos,
-                })
-            })
-            .for_each(|next_state| {
-                if next_state.cost < *dist.get(&next_state.position).unwrap() {
-                    heap.push(next_state);
-                    *dist.get_mut(&next_state.position).unwrap() = next_state.cost;
-                }
-            });
+        for next_pos in costs.neighbours(position) {
+            let next_state = State {
+                cost: cost + costs[next_pos],
+                position: next_pos,
+            };
+            if next_state.cost < dist[next_pos] {
+                heap.push(next_state);
+                dist[next_pos] = next_state.cost;
+            }
+        }
     }
-    unreachable!()
+    Err(Error::NoSolution(format!("no path to {:?}", goal)))
 }
 
-pub fn solution_1<P>(filename: P) -> u64
+fn parse_costs<I>(input: I) -> Result<Grid<u64>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let costs: HashMap<(i32, i32), u64> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row_idx, line)| {
-            line.unwrap()
-                .char_indices()
-                .map(|(col_idx, c)| {
-                    (
-                        (row_idx as i32, col_idx as i32),
-                        c.to_digit(10).unwrap() as u64,
-                    )
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-    let goal = costs.keys().max().unwrap();
+    // risk levels are 1 through 9
+    parse_chars(input, |c| {
+        c.to_digit(10).filter(|risk| *risk > 0).map(u64::from)
+    })
+}
+
+fn lowest_risk(costs: &Grid<u64>) -> Result<u64> {
+    let goal = (costs.rows() - 1, costs.cols() - 1);
+
+    shortest_path(costs, (0, 0), goal)
+}
 
-    shortest_path(&costs, &(0, 0), goal)
+fn lowest_risk_full_map(costs: &Grid<u64>) -> Result<u64> {
+    // each tile to the right or below is one riskier, wrapping from 9 back to 1
+    let full_map = costs.tile(5, 5, |cost, (tile_r, tile_c)| {
+        (cost + (tile_r as u64) + (tile_c as u64) - 1) % 9 + 1
+    });
+    trace!("{}", Format::Ascii.render(&full_map));
+    lowest_risk(&full_map)
 }
 
-pub fn solution_2<P>(filename: P) -> u64
+pub fn solution_1<I>(input: I) -> Result<u64>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let mut num_rows: i32 = 0;
-    let mut num_cols: i32 = 0;
-    let costs: HashMap<(i32, i32), u64> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row_idx, line)| {
-            num_rows = max(num_rows, row_idx as i32);
-            line.unwrap()
-                .char_indices()
-                .map(|(col_idx, c)| {
-                    num_cols = max(num_cols, col_idx as i32);
-                    (
-                        (row_idx as i32, col_idx as i32),
-                        c.to_digit(10).unwrap() as u64,
-                    )
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-    // println!("{}", render_map(&costs));
-    num_rows += 1;
-    num_cols += 1;
-
-    let others = costs
-        .into_iter()
-        .map(|((orig_r, orig_c), orig_cost)| {
-            (0..5)
-                .into_iter()
-                .map(|cave_r| {
-                    (0..5)
-                        .into_iter()
-                        .map(|cave_c| {
-                            (
-                                (cave_r * num_rows + orig_r, cave_c * num_cols + orig_c),
-                                (orig_cost + (cave_r as u64) + (cave_c as u64) - 1) % 9 + 1,
-                            )
-                        })
-                        .collect::<Vec<((i32, i32), u64)>>()
-                })
-                .flatten()
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect::<HashMap<(i32, i32), u64>>();
-
-    // println!("{}", render_map(&others));
-    let goal = others.keys().max().unwrap();
+    lowest_risk(&parse_costs(input)?)
+}
 
-    shortest_path(&others, &(0, 0), goal)
+pub fn solution_2<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    lowest_risk_full_map(&parse_costs(input)?)
 }
 
-// pub fn solution_2<P>(filename: P) -> usize
-// where
-//     P: AsRef<Path>,
-// {
-//     let mut lines = read_lines(filename).expect("failed to read input");
-//     unimplemented!()
-// }
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Puzzle = Grid<u64>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: AnyLines) -> Result<Grid<u64>> {
+        parse_costs(input)
+    }
+
+    fn part_1(&self, costs: &Grid<u64>) -> Result<u64> {
+        lowest_risk(costs)
+    }
+
+    fn part_2(&self, costs: &Grid<u64>) -> Result<u64> {
+        lowest_risk_full_map(costs)
+    }
+}
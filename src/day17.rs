@@ -1,8 +1,16 @@
 use std::cmp::min;
 
+use itertools::Itertools;
+
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
+
 fn num_possible_x(xbound: (u64, u64), step_bound: (u64, u64)) -> usize {
     // you might be able to cache the (step, bound) -> count, but whatever
-    println!("steps: {:?}", step_bound);
+    trace!("steps: {:?}", step_bound);
     let (x_low, x_high) = xbound;
     let (raw_step_low, raw_step_high) = step_bound;
     // x + max((x - 1), 0) + max((x - 2), 0) + ...
@@ -15,7 +23,7 @@ fn num_possible_x(xbound: (u64, u64), step_bound: (u64, u64)) -> usize {
         for step in step_low..(step_high + 1) {
             let x_pos = step * x_vel - (step * (step - 1)) / 2;
             if x_low <= x_pos && x_pos <= x_high {
-                println!("x: {:?}", x_vel);
+                trace!("x: {:?}", x_vel);
                 to_ret += 1;
                 break;
             }
@@ -41,6 +49,41 @@ fn num_steps(y_vel: i64, ybound: (i64, i64)) -> Option<(u64, u64)> {
     }
 }
 
+// parses "target area: x=20..30, y=-10..-5" into (xbound, ybound)
+pub fn read_target_area<I>(input: I) -> Result<((u64, u64), (i64, i64))>
+where
+    I: Input,
+{
+    let line = input.into_lines()?.next_line()?;
+    let (xbound, ybound) = line
+        .strip_prefix("target area: ")
+        .ok_or_else(|| line.error("expected \"target area:\""))?
+        .split(", ")
+        .map(|range_str| {
+            let (low, high) = range_str
+                .split('=')
+                .nth(1)
+                .and_then(|range| range.split("..").collect_tuple())
+                .ok_or_else(|| line.error(format!("bad range {:?}", range_str)))?;
+            Ok((
+                line.parse_field::<i64>(low)?,
+                line.parse_field::<i64>(high)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .collect_tuple::<((i64, i64), (i64, i64))>()
+        .ok_or_else(|| line.error("expected an x and a y range"))?;
+    // the solutions assume the target is to the right of and below the probe
+    if xbound.0 <= 0 || xbound.0 > xbound.1 {
+        return Err(line.error("x range must be positive and ascending"));
+    }
+    if ybound.1 >= 0 || ybound.0 > ybound.1 {
+        return Err(line.error("y range must be negative and ascending"));
+    }
+    Ok(((xbound.0 as u64, xbound.1 as u64), ybound))
+}
+
 pub fn solution_1(_: (u64, u64), ybound: (i64, i64)) -> i64 {
     let (y_low, _) = ybound;
     y_low * (y_low + 1) / 2
@@ -52,11 +95,31 @@ pub fn solution_2(xbound: (u64, u64), ybound: (i64, i64)) -> usize {
     assert!(y_low < 0 && y_high < 0);
     let mut total = 0;
     for y_vel in y_low..-y_low {
-        println!("y_vel: {}", y_vel);
+        trace!("y_vel: {}", y_vel);
         if let Some(step_bound) = num_steps(y_vel, ybound) {
             total += num_possible_x(xbound, step_bound);
         }
-        println!("total: {:?}", total);
+        trace!("total: {:?}", total);
     }
     total
 }
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Puzzle = ((u64, u64), (i64, i64));
+    type Answer1 = i64;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<((u64, u64), (i64, i64))> {
+        read_target_area(input)
+    }
+
+    fn part_1(&self, &(xbound, ybound): &((u64, u64), (i64, i64))) -> Result<i64> {
+        Ok(solution_1(xbound, ybound))
+    }
+
+    fn part_2(&self, &(xbound, ybound): &((u64, u64), (i64, i64))) -> Result<usize> {
+        Ok(solution_2(xbound, ybound))
+    }
+}
@@ -1,276 +1,158 @@
-use std::{collections::VecDeque, fmt::Debug, ops::Add, path::Path, str::FromStr};
-
-use crate::util::read_lines;
-
-#[derive(Debug, Clone)]
-enum Node {
-    Pair(Box<Pair>),
-    Lit(u32),
+use std::thread;
+
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    log::{self, Level},
+    parallel,
+    snailfish::{Flat, Number},
+    solution::Solution,
+};
+
+// generic so both representations can be benchmarked against each other
+pub fn parse_numbers<N, I>(input: I) -> Result<Vec<N>>
+where
+    N: Number,
+    I: Input,
+{
+    let mut lines = input.into_lines()?;
+    let pairs = (&mut lines)
+        .map(|line| line?.parse::<N>())
+        .collect::<Result<Vec<_>>>()?;
+    if pairs.is_empty() {
+        return Err(lines.error("no snailfish numbers"));
+    }
+    Ok(pairs)
 }
 
-#[derive(Debug, Clone)]
-struct Pair {
-    left: Node,
-    right: Node,
+pub fn sum_magnitude<N>(pairs: &[N]) -> u64
+where
+    N: Number,
+    Option<N>: for<'a> std::iter::Sum<&'a N>,
+{
+    let res = if log::enabled(Level::Trace) {
+        let mut pairs = pairs.iter().cloned();
+        let first = pairs.next().unwrap();
+        pairs.fold(first, |acc, new| {
+            trace!("  {}\n+ {}", acc, new);
+            let (res, steps) = acc.add_traced(new);
+            for step in steps {
+                trace!("{}", step);
+            }
+            trace!("= {}", res);
+            res
+        })
+    } else {
+        pairs.iter().sum::<Option<N>>().unwrap()
+    };
+    debug!("{}", res);
+    res.magnitude()
 }
 
-#[derive(Debug)]
-enum ParseNode {
-    Node(Node),
-    Open,
+// the two different numbers (by index) whose sum has the largest magnitude
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargestSum {
+    pub first: usize,
+    pub second: usize,
+    pub magnitude: u64,
 }
 
-impl FromStr for Pair {
-    type Err = std::io::Error;
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let mut stack: VecDeque<ParseNode> = VecDeque::new();
-        line.chars().for_each(|c| match c {
-            '[' => {
-                stack.push_back(ParseNode::Open);
-            }
-            ',' => (),
-            ']' => match (stack.pop_back().unwrap(), stack.pop_back().unwrap()) {
-                (ParseNode::Node(right), ParseNode::Node(left)) => {
-                    match stack.pop_back().unwrap() {
-                        ParseNode::Open => stack
-                            .push_back(ParseNode::Node(Node::Pair(Box::new(Pair { left, right })))),
-                        _ => unreachable!(),
-                    }
-                }
-                _ => unreachable!(),
-            },
-            _ => stack.push_back(ParseNode::Node(Node::Lit(c.to_digit(10).unwrap()))),
-        });
-        match stack.pop_back().unwrap() {
-            ParseNode::Node(Node::Pair(pair)) => Ok(*pair),
-            _ => unreachable!(),
+// every ordered pair gets added, with the first numbers dealt out between `threads`
+// threads. ties go to the earliest pair so the answer doesn't depend on the thread count
+pub fn largest_sum<N>(numbers: &[N], threads: usize) -> Option<LargestSum>
+where
+    N: Number + Sync,
+{
+    let threads = threads.clamp(1, numbers.len().max(1));
+    let best_of = |first: usize| {
+        (0..numbers.len())
+            .filter(move |&second| second != first)
+            .map(move |second| LargestSum {
+                first,
+                second,
+                magnitude: (numbers[first].clone() + numbers[second].clone()).magnitude(),
+            })
+    };
+    let pick = |best: Option<LargestSum>, sum: LargestSum| match best {
+        Some(best)
+            if (best.magnitude, std::cmp::Reverse((best.first, best.second)))
+                >= (sum.magnitude, std::cmp::Reverse((sum.first, sum.second))) =>
+        {
+            Some(best)
         }
-    }
+        _ => Some(sum),
+    };
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                scope.spawn(move || {
+                    (thread..numbers.len())
+                        .step_by(threads)
+                        .flat_map(best_of)
+                        .fold(None, pick)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .fold(None, pick)
+    })
 }
 
-impl Add for Pair {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        let mut res = Self {
-            left: Node::Pair(Box::new(self)),
-            right: Node::Pair(Box::new(other)),
-        };
-        res.reduce();
-        res
+pub fn max_pair_magnitude<N>(pairs: &[N]) -> u64
+where
+    N: Number + Sync,
+{
+    match largest_sum(pairs, parallel::threads()) {
+        Some(LargestSum {
+            first,
+            second,
+            magnitude,
+        }) => {
+            info!(
+                "largest magnitude from line {} + line {}: {} + {}",
+                first + 1,
+                second + 1,
+                pairs[first],
+                pairs[second]
+            );
+            magnitude
+        }
+        None => 0,
     }
 }
 
-enum Action {
-    Exploded(bool, Option<u32>, Option<u32>),
-    Nothing,
+pub fn solution_1<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    Ok(sum_magnitude(&parse_numbers::<Flat, _>(input)?))
 }
 
-impl Pair {
-    fn reduce(&mut self) {
-        loop {
-            if self.explode() {
-                continue;
-            } else if !self.split() {
-                break;
-            }
-        }
-    }
-
-    fn send_left(&mut self, going_down: bool, val: &u32) -> bool {
-        if !going_down {
-            match &mut self.right {
-                Node::Lit(curr) => {
-                    *curr += *val;
-                    return false;
-                }
-                Node::Pair(p) => {
-                    let cont = (*p).send_left(false, val);
-                    if !cont {
-                        return false;
-                    }
-                }
-            }
-        }
-
-        match &mut self.left {
-            Node::Lit(curr) => {
-                *curr += *val;
-                false
-            }
-            Node::Pair(p) => (*p).send_left(false, val),
-        }
-    }
-
-    fn send_right(&mut self, going_down: bool, val: &u32) -> bool {
-        if !going_down {
-            match &mut self.left {
-                Node::Lit(curr) => {
-                    *curr += *val;
-                    return false;
-                }
-                Node::Pair(p) => {
-                    let cont = (*p).send_right(false, val);
-                    if !cont {
-                        return false;
-                    }
-                }
-            }
-        }
-
-        match &mut self.right {
-            Node::Lit(curr) => {
-                *curr += *val;
-                false
-            }
-            Node::Pair(p) => (*p).send_right(false, val),
-        }
-    }
+pub fn solution_2<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    Ok(max_pair_magnitude(&parse_numbers::<Flat, _>(input)?))
+}
 
-    fn explode_helper(&mut self, depth: u32) -> Action {
-        if depth == 0 {
-            match (&self.left, &self.right) {
-                (Node::Lit(l), Node::Lit(r)) => Action::Exploded(true, Some(*l), Some(*r)),
-                _ => unreachable!(),
-            }
-        } else {
-            match &mut self.left {
-                Node::Lit(_) => (),
-                Node::Pair(p) => match p.explode_helper(depth - 1) {
-                    Action::Exploded(just_explode, maybe_left, maybe_right) => {
-                        if just_explode {
-                            self.left = Node::Lit(0);
-                        }
-                        let bubble_right = {
-                            if let Some(right) = maybe_right {
-                                if self.send_right(true, &right) {
-                                    Some(right)
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
-                        };
-                        return Action::Exploded(false, maybe_left, bubble_right);
-                    }
-                    Action::Nothing => (),
-                },
-            }
-            match &mut self.right {
-                Node::Lit(_) => Action::Nothing,
-                Node::Pair(p) => match p.explode_helper(depth - 1) {
-                    Action::Exploded(just_explode, maybe_left, maybe_right) => {
-                        if just_explode {
-                            self.right = Node::Lit(0);
-                        }
-                        let bubble_left = {
-                            if let Some(left) = maybe_left {
-                                if self.send_left(true, &left) {
-                                    Some(left)
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
-                        };
-                        Action::Exploded(false, bubble_left, maybe_right)
-                    }
-                    Action::Nothing => Action::Nothing,
-                },
-            }
-        }
-    }
+pub struct Day18;
 
-    fn explode(&mut self) -> bool {
-        match self.explode_helper(4) {
-            Action::Nothing => false,
-            Action::Exploded(_, _, _) => true,
-        }
-    }
+impl Solution for Day18 {
+    type Puzzle = Vec<Flat>;
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-    fn split(&mut self) -> bool {
-        match &mut self.left {
-            &mut Node::Lit(l) => {
-                if l >= 10 {
-                    self.left = Node::Pair(Box::new(Pair {
-                        left: Node::Lit(l / 2),
-                        right: Node::Lit((l + 1) / 2),
-                    }));
-                    return true;
-                }
-            }
-            Node::Pair(p) => {
-                if p.split() {
-                    return true;
-                }
-            }
-        }
-        match &mut self.right {
-            &mut Node::Lit(l) => {
-                if l >= 10 {
-                    self.right = Node::Pair(Box::new(Pair {
-                        left: Node::Lit(l / 2),
-                        right: Node::Lit((l + 1) / 2),
-                    }));
-                    return true;
-                }
-            }
-            Node::Pair(p) => {
-                if p.split() {
-                    return true;
-                }
-            }
-        }
-        false
-    }
-
-    fn magnitude(&self) -> u64 {
-        (self.left.magnitude() as u64) * 3 + (self.right.magnitude() as u64) * 2
+    fn parse(&self, input: AnyLines) -> Result<Vec<Flat>> {
+        parse_numbers(input)
     }
-}
 
-impl Node {
-    fn magnitude(&self) -> u64 {
-        match self {
-            Node::Pair(p) => p.magnitude(),
-            Node::Lit(l) => *l as u64,
-        }
+    fn part_1(&self, pairs: &Vec<Flat>) -> Result<u64> {
+        Ok(sum_magnitude(pairs))
     }
-}
-
-pub fn solution_1<P>(filename: P) -> u64
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    let mut pairs = lines
-        .into_iter()
-        .map(|line| line.unwrap().parse::<Pair>().unwrap());
-    let first = pairs.next().unwrap();
-    let res = pairs.fold(first, |acc, new| acc + new);
-    println!("{:#?}", res);
-    res.magnitude()
-}
 
-pub fn solution_2<P>(filename: P) -> u64
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    let pairs: Vec<_> = lines
-        .into_iter()
-        .map(|line| line.unwrap().parse::<Pair>().unwrap())
-        .collect();
-    let mut max = 0;
-    for i in 0..pairs.len() {
-        for j in 0..pairs.len() {
-            if i == j {
-                continue;
-            }
-            max = std::cmp::max((pairs[i].clone() + pairs[j].clone()).magnitude(), max)
-        }
+    fn part_2(&self, pairs: &Vec<Flat>) -> Result<u64> {
+        Ok(max_pair_magnitude(pairs))
     }
-    max
 }
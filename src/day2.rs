@@ -1,64 +1,47 @@
-use std::path::Path;
-
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 #[derive(Add, Sum)]
 struct Offset(i32, i32);
 
-pub fn solution_1<P>(fname: P) -> i32
+fn parse_commands<I>(input: I) -> Result<Vec<(String, i32)>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(fname).expect("failed to read input");
-    let offset = lines
-        .into_iter()
+    input
+        .into_lines()?
         .map(|line| {
-            line.expect("couldn't find line")
-                .split_whitespace()
-                .into_iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>()
-        })
-        .map(|split_lines| {
-            (
-                split_lines[0].clone(),
-                split_lines[1]
-                    .parse::<i32>()
-                    .expect("couldn't parse magnitude"),
-            )
+            let line = line?;
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [direction @ ("forward" | "down" | "up"), magnitude] => {
+                    Ok((direction.to_string(), line.parse_field(magnitude)?))
+                }
+                [direction, _] => Err(line.error(format!("unknown direction {:?}", direction))),
+                _ => Err(line.error("expected a direction and a magnitude")),
+            }
         })
+        .collect()
+}
+
+fn final_position(commands: &[(String, i32)]) -> i32 {
+    let offset = commands
+        .iter()
         .map(|(direction, magnitude)| match direction.as_str() {
-            "forward" => Offset(magnitude, 0),
-            "down" => Offset(0, magnitude),
+            "forward" => Offset(*magnitude, 0),
+            "down" => Offset(0, *magnitude),
             "up" => Offset(0, -magnitude),
-            _ => unimplemented!(),
+            _ => unreachable!(),
         })
         .sum::<Offset>();
     offset.0 * offset.1
 }
 
-pub fn solution_2<P>(fname: P) -> i32
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(fname).expect("failed to read input");
-    let offset = lines
-        .into_iter()
-        .map(|line| {
-            line.expect("couldn't find line")
-                .split_whitespace()
-                .into_iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>()
-        })
-        .map(|split_lines| {
-            (
-                split_lines[0].clone(),
-                split_lines[1]
-                    .parse::<i32>()
-                    .expect("couldn't parse magnitude"),
-            )
-        })
+fn final_position_with_aim(commands: &[(String, i32)]) -> i32 {
+    let offset = commands
+        .iter()
         .fold(
             (0, Offset(0, 0)),
             |state, (direction, magnitude)| match direction.as_str() {
@@ -68,9 +51,43 @@ where
                     state.0,
                     Offset((state.1).0 + magnitude, (state.1).1 + (state.0 * magnitude)),
                 ),
-                _ => unimplemented!(),
+                _ => unreachable!(),
             },
         )
         .1;
     offset.0 * offset.1
 }
+
+pub fn solution_1<I>(input: I) -> Result<i32>
+where
+    I: Input,
+{
+    Ok(final_position(&parse_commands(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<i32>
+where
+    I: Input,
+{
+    Ok(final_position_with_aim(&parse_commands(input)?))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Puzzle = Vec<(String, i32)>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<(String, i32)>> {
+        parse_commands(input)
+    }
+
+    fn part_1(&self, commands: &Vec<(String, i32)>) -> Result<i32> {
+        Ok(final_position(commands))
+    }
+
+    fn part_2(&self, commands: &Vec<(String, i32)>) -> Result<i32> {
+        Ok(final_position_with_aim(commands))
+    }
+}
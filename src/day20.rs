@@ -1,137 +1,171 @@
-use std::{collections::HashSet, path::Path};
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+    util::{parse_chars, Grid},
+};
 
-use itertools::Itertools;
-
-use crate::util::read_lines;
-
-type Coord = (i64, i64);
-
-struct Board {
-    enhancement_algo: HashSet<usize>,
-    foreground_lights: HashSet<Coord>,
-    foreground_row_bound: (i64, i64),
-    foreground_col_bound: (i64, i64),
+#[derive(Clone)]
+pub struct Board {
+    enhancement_algo: Vec<bool>,
+    foreground_lights: Grid<bool>,
     is_background_lit: bool,
 }
 
 impl Board {
-    fn is_coord_lit(&self, coord: &Coord) -> bool {
-        let (row, col) = coord;
-        if row < &self.foreground_row_bound.0
-            || row > &self.foreground_row_bound.1
-            || col < &self.foreground_col_bound.0
-            || col > &self.foreground_col_bound.1
-        {
-            // out of bounds
-            self.is_background_lit
-        } else {
-            self.foreground_lights.contains(coord)
+    // anything off the foreground is part of the infinite background
+    fn is_coord_lit(&self, row: isize, col: isize) -> bool {
+        if row < 0 || col < 0 {
+            return self.is_background_lit;
         }
+        *self
+            .foreground_lights
+            .get((row as usize, col as usize))
+            .unwrap_or(&self.is_background_lit)
     }
 
+    // the foreground grows by one in every direction, since those pixels can see it
     fn step(&mut self) {
-        let (row_min, row_max) = self.foreground_row_bound;
-        let (col_min, col_max) = self.foreground_col_bound;
-        let new_foreground: HashSet<Coord> = ((row_min - 1)..(row_max + 2))
-            .cartesian_product((col_min - 1)..(col_max + 2))
-            .filter_map(|(row, col)| {
-                let binary = (-1..2).cartesian_product(-1..2).fold(
-                    String::from(""),
-                    |mut acc, (row_off, col_off)| {
-                        if self.is_coord_lit(&(row + row_off, col + col_off)) {
-                            acc.push('1');
-                        } else {
-                            acc.push('0');
-                        }
-                        acc
-                    },
-                );
-                if self
-                    .enhancement_algo
-                    .contains(&usize::from_str_radix(&binary, 2).unwrap())
-                {
-                    Some((row, col))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let lights = &self.foreground_lights;
+        let new_foreground = Grid::from_fn(lights.rows() + 2, lights.cols() + 2, |(row, col)| {
+            let (row, col) = (row as isize - 1, col as isize - 1);
+            let index = (-1..2)
+                .flat_map(|row_off| (-1..2).map(move |col_off| (row_off, col_off)))
+                .fold(0, |acc, (row_off, col_off)| {
+                    acc << 1 | self.is_coord_lit(row + row_off, col + col_off) as usize
+                });
+            self.enhancement_algo[index]
+        });
         self.foreground_lights = new_foreground;
-        if self.enhancement_algo.contains(&0) && !self.is_background_lit {
-            self.is_background_lit = true;
-        } else if !self.enhancement_algo.contains(&511) && self.is_background_lit {
-            self.is_background_lit = false;
-        }
-        self.foreground_col_bound = (col_min - 1, col_max + 1);
-        self.foreground_row_bound = (row_min - 1, row_max + 1);
+        self.is_background_lit = if self.is_background_lit {
+            self.enhancement_algo[511]
+        } else {
+            self.enhancement_algo[0]
+        };
+    }
+
+    // the foreground with `margin` pixels of background around it, so that frames from
+    // different steps line up and the background can be seen flipping
+    fn padded(&self, margin: usize) -> Grid<bool> {
+        let lights = &self.foreground_lights;
+        Grid::from_fn(
+            lights.rows() + 2 * margin,
+            lights.cols() + 2 * margin,
+            |(row, col)| {
+                self.is_coord_lit(
+                    row as isize - margin as isize,
+                    col as isize - margin as isize,
+                )
+            },
+        )
+    }
+}
+
+fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
     }
 }
 
-fn get_board<P>(filename: P) -> Board
+fn get_board<I>(input: I) -> Result<Board>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let enhancement_algo: HashSet<usize> = lines
-        .next()
-        .unwrap()
-        .unwrap()
-        .char_indices()
-        .filter_map(|(i, c)| match c {
-            '#' => Some(i),
-            '.' => None,
-            _ => unreachable!(),
-        })
-        .collect();
-    let _ = lines.next();
-    let lights: HashSet<Coord> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row_idx, line)| {
-            line.unwrap()
-                .char_indices()
-                .filter_map(|(col_idx, c)| {
-                    if c == '#' {
-                        Some((row_idx as i64, col_idx as i64))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
+    let mut lines = input.into_lines()?;
+    let algo_line = lines.next_line()?;
+    if algo_line.len() != 512 {
+        return Err(algo_line.error("enhancement algorithm must be 512 characters"));
+    }
+    let enhancement_algo = algo_line
+        .chars()
+        .map(|c| {
+            parse_pixel(c).ok_or_else(|| algo_line.error(format!("unexpected character {:?}", c)))
         })
-        .flatten()
-        .collect();
-
-    let row_max = lights.iter().map(|(row, _)| *row).max().unwrap();
-    let col_max = lights.iter().map(|(_, col)| *col).max().unwrap();
+        .collect::<Result<Vec<bool>>>()?;
+    let blank = lines.next_line()?;
+    if !blank.trim().is_empty() {
+        return Err(blank.error("expected a blank line after the algorithm"));
+    }
 
-    Board {
+    Ok(Board {
         enhancement_algo,
-        foreground_lights: lights,
-        foreground_row_bound: (0, row_max),
-        foreground_col_bound: (0, col_max),
+        foreground_lights: parse_chars(lines, parse_pixel)?,
         is_background_lit: false,
+    })
+}
+
+fn enhanced(board: &Board, steps: usize) -> Board {
+    let mut board = board.clone();
+    for i in 0..steps {
+        debug!("step: {}", i);
+        board.step();
     }
+    board
+}
+
+fn lit_after(board: &Board, steps: usize) -> usize {
+    let board = enhanced(board, steps);
+    board.foreground_lights.values().filter(|lit| **lit).count()
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+// the part of the image that isn't background after `steps` enhancements, for rendering
+pub fn lights_after<I>(input: I, steps: usize) -> Result<Grid<bool>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut board = get_board(filename);
-    board.step();
-    board.step();
-    board.foreground_lights.len()
+    Ok(enhanced(&get_board(input)?, steps).foreground_lights)
 }
 
-pub fn solution_2<P>(filename: P) -> usize
+// every generation up to `limit` enhancements, or the two part 1 needs if there's no
+// limit, all padded to the size of the last one for animating
+pub fn generations<I>(input: I, limit: Option<usize>) -> Result<Vec<Grid<bool>>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut board = get_board(filename);
-    for i in 0..50 {
-        println!("step: {}", i);
-        board.step();
+    let steps = limit.unwrap_or(2);
+    let mut board = get_board(input)?;
+    let mut frames = Vec::new();
+    for i in 0..=steps {
+        if i > 0 {
+            board.step();
+        }
+        frames.push(board.padded(steps - i));
+    }
+    Ok(frames)
+}
+
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(lit_after(&get_board(input)?, 2))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(lit_after(&get_board(input)?, 50))
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Puzzle = Board;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Board> {
+        get_board(input)
+    }
+
+    fn part_1(&self, board: &Board) -> Result<usize> {
+        Ok(lit_after(board, 2))
+    }
+
+    fn part_2(&self, board: &Board) -> Result<usize> {
+        Ok(lit_after(board, 50))
     }
-    board.foreground_lights.len()
 }
@@ -2,6 +2,12 @@ use std::{cmp::max, collections::HashMap, iter::repeat_with};
 
 use itertools::Itertools;
 
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
+
 #[derive(Add, Sum, Clone, Copy)]
 struct Scores(u64, u64);
 
@@ -39,16 +45,35 @@ fn simulate_to_win(
     unreachable!()
 }
 
+// parses "Player 1 starting position: 8" for both players
+pub fn read_starting_positions<I>(input: I) -> Result<(u64, u64)>
+where
+    I: Input,
+{
+    let mut lines = input.into_lines()?;
+    let mut position = || -> Result<u64> {
+        let line = lines.next_line()?;
+        let (_, pos) = line
+            .rsplit_once(": ")
+            .ok_or_else(|| line.error("expected a starting position"))?;
+        match line.parse_field::<u64>(pos)? {
+            pos @ 1..=10 => Ok(pos),
+            pos => Err(line.error(format!("position {} is off the board", pos))),
+        }
+    };
+    Ok((position()?, position()?))
+}
+
 pub fn solution_1(p1: u64, p2: u64) -> u64 {
     let mut curr = 1;
     let mut deterministic_dice = repeat_with(|| {
         let tmp = curr;
         curr = curr % 100 + 1;
-        println!("curr: {}", curr);
+        trace!("curr: {}", curr);
         tmp
     });
     let (losing_score, turns) = simulate_to_win(p1, p2, &mut deterministic_dice);
-    println!("{} {}", losing_score, turns);
+    debug!("{} {}", losing_score, turns);
     losing_score * turns
 }
 
@@ -121,3 +146,23 @@ pub fn solution_2(p1: u64, p2: u64) -> u64 {
     let Scores(p1_wins, p2_wins) = num_wins(&mut dp, &possibilities, true, p1, 0, p2, 0);
     max(p1_wins, p2_wins)
 }
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Puzzle = (u64, u64);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: AnyLines) -> Result<(u64, u64)> {
+        read_starting_positions(input)
+    }
+
+    fn part_1(&self, &(p1, p2): &(u64, u64)) -> Result<u64> {
+        Ok(solution_1(p1, p2))
+    }
+
+    fn part_2(&self, &(p1, p2): &(u64, u64)) -> Result<u64> {
+        Ok(solution_2(p1, p2))
+    }
+}
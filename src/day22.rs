@@ -1,238 +1,232 @@
-use std::{
-    collections::{BTreeMap, HashMap, HashSet},
-    path::Path,
-};
-
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    boxes::{BoxSet, Cuboid},
+    error::Result,
+    input::{AnyLines, Input, Line, Lines},
+    solution::Solution,
+};
 
-type Range = (i32, i32);
-type Cube = (Range, Range, Range);
+pub type Cube = Cuboid<3>;
 
-fn parse_line(line: String) -> (bool, Cube) {
-    let mut split = line.split_whitespace();
-    let is_on = match split.next().unwrap() {
+fn parse_line(line: &Line) -> Result<(bool, Cube)> {
+    let (state, ranges) = line
+        .split_whitespace()
+        .collect_tuple()
+        .ok_or_else(|| line.error("expected a step like \"on x=..,y=..,z=..\""))?;
+    let is_on = match state {
         "on" => true,
         "off" => false,
-        _ => unreachable!(),
+        _ => return Err(line.error(format!("unknown state {:?}", state))),
     };
-    let ((xmin, xmax), (ymin, ymax), (zmin, zmax)) = split
-        .next()
-        .unwrap()
+    Ok((is_on, parse_cube(line, ranges)?))
+}
+
+fn parse_cube(line: &Line, ranges: &str) -> Result<Cube> {
+    let (x, y, z) = ranges
         .split(',')
         .map(|range_str| {
-            let range = range_str.split('=').nth(1).unwrap();
-            range
-                .split("..")
-                .map(|bound| bound.parse::<i32>().unwrap())
-                .collect_tuple()
-                .unwrap()
+            let (min, max) = range_str
+                .split('=')
+                .nth(1)
+                .and_then(|range| range.split("..").collect_tuple())
+                .ok_or_else(|| line.error(format!("bad range {:?}", range_str)))?;
+            let (min, max) = (line.parse_field::<i64>(min)?, line.parse_field::<i64>(max)?);
+            if min > max {
+                return Err(line.error(format!("range {:?} is backwards", range_str)));
+            }
+            Ok((min, max))
         })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
         .collect_tuple()
-        .unwrap();
-    (is_on, ((xmin, xmax), (ymin, ymax), (zmin, zmax)))
+        .ok_or_else(|| line.error("expected x, y and z ranges"))?;
+    Ok(Cuboid::from_inclusive([x, y, z]))
+}
+
+// a region to query, written like a step without the on or off
+pub fn parse_region(region: &str) -> Result<Cube> {
+    let line = Lines::new("region", region.as_bytes()).next_line()?;
+    parse_cube(&line, &line)
 }
 
-fn clamp<T>(v: T, min: T, max: T) -> T
+pub fn parse_steps<I>(input: I) -> Result<Vec<(bool, Cube)>>
 where
-    T: PartialOrd,
+    I: Input,
 {
-    if v < min {
-        min
-    } else if max < v {
-        max
-    } else {
-        v
-    }
+    input.into_lines()?.map(|line| parse_line(&line?)).collect()
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+// the cubes that are on once every step has been applied in order
+pub fn reboot<'a, S>(steps: S) -> BoxSet<3>
 where
-    P: AsRef<Path>,
+    S: IntoIterator<Item = &'a (bool, Cube)>,
 {
-    const BOUND: i32 = 50;
-    let lines = read_lines(filename).expect("failed to read input");
-    let coords: HashSet<(i32, i32, i32)> = lines
+    steps
         .into_iter()
-        .map(|line| parse_line(line.unwrap()))
-        .fold(HashSet::new(), |mut acc, input| {
-            let (is_on, ((xmin, xmax), (ymin, ymax), (zmin, zmax))) = input;
-            if xmin > BOUND
-                || ymin > BOUND
-                || zmin > BOUND
-                || xmax < -BOUND
-                || ymax < -BOUND
-                || zmax < -BOUND
-            {
-                return acc;
+        .fold(BoxSet::new(), |mut reactor, (is_on, cube)| {
+            trace!("{} {}", if *is_on { "on" } else { "off" }, cube);
+            if *is_on {
+                reactor.insert(*cube);
+            } else {
+                reactor.remove(cube);
             }
-            let xmin = clamp(xmin, -BOUND, BOUND);
-            let xmax = clamp(xmax, -BOUND, BOUND);
-            let ymin = clamp(ymin, -BOUND, BOUND);
-            let ymax = clamp(ymax, -BOUND, BOUND);
-            let zmin = clamp(zmin, -BOUND, BOUND);
-            let zmax = clamp(zmax, -BOUND, BOUND);
-            println!(
-                "{} {}..{} {}..{} {}..{}",
-                is_on, xmin, xmax, ymin, ymax, zmin, zmax
-            );
-            (xmin..(xmax + 1))
-                .cartesian_product(ymin..(ymax + 1))
-                .cartesian_product(zmin..(zmax + 1))
-                .into_iter()
-                .for_each(|((x, y), z)| {
-                    if is_on {
-                        acc.insert((x, y, z));
-                    } else {
-                        acc.remove(&(x, y, z));
-                    }
-                });
-            acc
-        });
-    coords.len()
+            reactor
+        })
 }
 
-struct Cuboids {
-    // allows querying from cube i -> cube & is_on
-    cuboids: Vec<(bool, Cube)>,
-    // allows ordered traversal from x position -> (cube idx, is_min_val)
-    x_ranges: Vec<(i32, (usize, bool))>,
+// the part of the reactor that initialization cares about
+pub fn initialization_region() -> Cube {
+    Cuboid::from_inclusive([(-50, 50); 3])
 }
 
-struct Squares {
-    // cube_idx -> (is_on, y range, z range)
-    cubes: HashMap<usize, (bool, Range, Range)>,
-    // sorted (y_pos, cube idx) -> is_range_start
-    y_ranges: BTreeMap<(i32, usize), bool>,
-    // // sorted (z_pos, cube idx) -> is_range_start
-    // z_ranges: BTreeMap<(i32, usize), bool>,
+fn count_initialization_on(steps: &[(bool, Cube)]) -> u128 {
+    let region = initialization_region();
+    let clipped: Vec<_> = steps
+        .iter()
+        .filter_map(|(is_on, cube)| Some((*is_on, cube.intersection(&region)?)))
+        .collect();
+    reboot(&clipped).volume()
 }
 
-impl Squares {
-    fn new() -> Self {
+fn count_num_on(steps: &[(bool, Cube)]) -> u128 {
+    let reactor = reboot(steps);
+    debug!("{} disjoint cuboids are on", reactor.cuboids().len());
+    reactor.volume()
+}
+
+// the state of the reactor part way through a reboot, which can be stepped forwards and
+// back one step at a time
+pub struct Reactor {
+    steps: Vec<(bool, Cube)>,
+    on: BoxSet<3>,
+    // for each step applied so far, what was on inside its cuboid before it ran, so it
+    // can be undone
+    overwritten: Vec<BoxSet<3>>,
+}
+
+impl Reactor {
+    // with none of the steps applied yet
+    pub fn new(steps: Vec<(bool, Cube)>) -> Self {
         Self {
-            cubes: HashMap::new(),
-            y_ranges: BTreeMap::new(),
-            // z_ranges: BTreeMap::new(),
+            steps,
+            on: BoxSet::new(),
+            overwritten: Vec::new(),
         }
     }
 
-    fn add_x(&mut self, cube_idx: usize, is_on: bool, y_range: Range, z_range: Range) {
-        self.cubes.insert(cube_idx, (is_on, y_range, z_range));
-        self.y_ranges.insert((y_range.0, cube_idx), true);
-        self.y_ranges.insert((y_range.1 + 1, cube_idx), false);
+    pub fn reboot(steps: Vec<(bool, Cube)>) -> Self {
+        let mut reactor = Self::new(steps);
+        while reactor.apply().is_some() {}
+        reactor
     }
 
-    fn remove_x(&mut self, cube_idx: usize) {
-        let (_, y_range, _) = self.cubes.get(&cube_idx).unwrap();
-        self.y_ranges.remove(&(y_range.0, cube_idx));
-        self.y_ranges.remove(&(y_range.1 + 1, cube_idx));
+    pub fn steps(&self) -> &[(bool, Cube)] {
+        &self.steps
     }
 
-    fn current_area(&self) -> usize {
-        // (z_pos, cube_idx) -> (is_start_range, is_on)
-        let mut active_z: BTreeMap<(i32, usize), (bool, bool)> = BTreeMap::new();
-        let y_status = self
-            .y_ranges
-            .iter()
-            .map(|((y_pos, cube_idx), is_range_start)| {
-                let (is_on, _, z_range) = self.cubes.get(cube_idx).unwrap();
-                if *is_range_start {
-                    active_z.insert((z_range.0, *cube_idx), (true, *is_on));
-                    active_z.insert((z_range.1 + 1, *cube_idx), (false, *is_on));
-                } else {
-                    active_z.remove(&(z_range.0, *cube_idx));
-                    active_z.remove(&(z_range.1 + 1, *cube_idx));
-                }
-
-                // sorted cube_idx -> is_on
-                let mut layer_state: BTreeMap<usize, bool> = BTreeMap::from([(0, false)]);
-                let z_status: Vec<_> = active_z
-                    .iter()
-                    .map(|((z, cube_idx), (is_start, is_on))| {
-                        if *is_start {
-                            layer_state.insert(*cube_idx, *is_on);
-                        } else {
-                            layer_state.remove(cube_idx);
-                        }
-                        let (_, is_final_on) = layer_state.iter().next_back().unwrap();
-                        (z, *is_final_on)
-                    })
-                    .collect();
-                let mut line_area = 0;
-                for i in 1..(z_status.len()) {
-                    let (z_start, is_on) = z_status[i - 1];
-                    let (z_end, _) = z_status[i];
-                    if is_on {
-                        line_area += z_end - z_start;
-                    }
-                }
-                (*y_pos, line_area)
-            })
-            .collect::<Vec<_>>();
-        let mut area = 0;
-        for i in 1..y_status.len() {
-            let (y_start, line_area) = y_status[i - 1];
-            let (y_end, _) = y_status[i];
-            area += (y_end - y_start) as usize * line_area as usize;
+    // how many of the steps have been applied
+    pub fn applied(&self) -> usize {
+        self.overwritten.len()
+    }
+
+    // applies the next step, returning the lit volume after it, or None once every
+    // step has been applied
+    pub fn apply(&mut self) -> Option<u128> {
+        let (is_on, cuboid) = *self.steps.get(self.applied())?;
+        trace!("{} {}", if is_on { "on" } else { "off" }, cuboid);
+        self.overwritten
+            .push(self.on.intersection(&BoxSet::from(cuboid)));
+        if is_on {
+            self.on.insert(cuboid);
+        } else {
+            self.on.remove(&cuboid);
         }
+        Some(self.lit_volume())
+    }
 
-        area
+    // takes back the last step applied, returning the lit volume after that, or None if
+    // there's nothing to undo
+    pub fn undo(&mut self) -> Option<u128> {
+        let overwritten = self.overwritten.pop()?;
+        let (_, cuboid) = self.steps[self.applied()];
+        trace!("undo {}", cuboid);
+        self.on.remove(&cuboid);
+        self.on = self.on.union(&overwritten);
+        Some(self.lit_volume())
     }
-}
 
-impl Cuboids {
-    fn new(cuboids: Vec<(bool, Cube)>) -> Self {
-        let x_ranges: Vec<(i32, (usize, bool))> = cuboids
-            .iter()
-            .enumerate()
-            .map(|(i, (_, ((x_min, x_max), _, _)))| {
-                [(*x_min, (i + 1, true)), (x_max + 1, (i + 1, false))]
-            })
-            .flatten()
-            .sorted()
-            .collect();
-        Self { cuboids, x_ranges }
+    pub fn is_on(&self, cube: [i64; 3]) -> bool {
+        self.on.contains(cube)
     }
 
-    fn count_num_on(self) -> usize {
-        let mut squares = Squares::new();
-        let x_status = self
-            .x_ranges
+    pub fn lit_volume(&self) -> u128 {
+        self.on.volume()
+    }
+
+    pub fn lit_within(&self, region: &Cube) -> u128 {
+        self.on.volume_within(region)
+    }
+
+    // the index of the last applied step whose cuboid covers the cube, which is what
+    // decided whether it's on. None if no step has touched it yet
+    pub fn last_touched(&self, cube: [i64; 3]) -> Option<usize> {
+        self.steps[..self.applied()]
             .iter()
-            .map(|(x, (cube_idx, is_start))| {
-                let (is_on, (_, y_range, z_range)) = self.cuboids[*cube_idx - 1];
-                if *is_start {
-                    squares.add_x(*cube_idx, is_on, y_range, z_range);
-                } else {
-                    squares.remove_x(*cube_idx);
-                }
-                let area = squares.current_area();
-                (*x, area)
-            })
-            .collect::<Vec<_>>();
-
-        let mut volume = 0;
-        for i in 1..x_status.len() {
-            let (x_start, area) = x_status[i - 1];
-            let (x_end, _) = x_status[i];
-            volume += (x_end - x_start) as usize * area;
-        }
+            .rposition(|(_, cuboid)| cuboid.contains(cube))
+    }
 
-        volume
+    // the cubes that are on as disjoint cuboids
+    pub fn lit_cuboids(&self) -> &[Cube] {
+        self.on.cuboids()
+    }
+
+    // the lit cuboids as "on" steps, one per line, which reboot to the same state
+    pub fn decomposition(&self) -> String {
+        self.lit_cuboids()
+            .iter()
+            .map(|cuboid| format!("on {}\n", cuboid))
+            .collect()
     }
 }
 
-pub fn solution_2<P>(filename: P) -> usize
+pub fn reactor<I>(input: I) -> Result<Reactor>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let coords = lines
-        .into_iter()
-        .map(|line| parse_line(line.unwrap()))
-        .collect::<Vec<_>>();
-    let cubes = Cuboids::new(coords);
-    cubes.count_num_on()
+    Ok(Reactor::reboot(parse_steps(input)?))
+}
+
+pub fn solution_1<I>(input: I) -> Result<u128>
+where
+    I: Input,
+{
+    Ok(count_initialization_on(&parse_steps(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<u128>
+where
+    I: Input,
+{
+    Ok(count_num_on(&parse_steps(input)?))
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Puzzle = Vec<(bool, Cube)>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<(bool, Cube)>> {
+        parse_steps(input)
+    }
+
+    fn part_1(&self, steps: &Vec<(bool, Cube)>) -> Result<u128> {
+        Ok(count_initialization_on(steps))
+    }
+
+    fn part_2(&self, steps: &Vec<(bool, Cube)>) -> Result<u128> {
+        Ok(count_num_on(steps))
+    }
 }
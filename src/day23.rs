@@ -6,9 +6,14 @@ use std::{
 use itertools::Itertools;
 
 use crate::day23::Amphipod::*;
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
-enum Amphipod {
+pub enum Amphipod {
     A = 0,
     B = 1,
     C = 2,
@@ -82,7 +87,7 @@ fn get_min_cost(
         return Some(0);
     }
     if let Some(cost) = dp.get(state) {
-        return (*cost).0;
+        return cost.0;
     }
     let mut min_cost = u64::MAX;
     let mut next_state = None;
@@ -150,7 +155,7 @@ fn get_min_cost(
                 let dest_spot = board.open_spots[idx];
                 let (hallway_offset, is_visitable) = if dest_spot < pos {
                     (
-                        ((pos as i64) - (dest_spot as i64)).abs() as u64,
+                        ((pos as i64) - (dest_spot as i64)).unsigned_abs(),
                         state
                             .hallway_pos
                             .keys()
@@ -158,7 +163,7 @@ fn get_min_cost(
                     )
                 } else {
                     (
-                        ((dest_spot as i64) - (pos as i64)).abs() as u64,
+                        ((dest_spot as i64) - (pos as i64)).unsigned_abs(),
                         state
                             .hallway_pos
                             .keys()
@@ -190,74 +195,112 @@ fn get_min_cost(
         Some(min_cost)
     };
     dp.insert(state.clone(), (to_ret, next_state));
-    if dp.len() % 1000 == 0 {
-        println!("states visited: {:?}", dp.len());
+    if dp.len().is_multiple_of(1000) {
+        debug!("states visited: {:?}", dp.len());
     }
     to_ret
 }
 
-pub fn solution_1() -> u64 {
+// sideroom rows of the burrow, from the hallway down
+type Burrow = Vec<[Amphipod; 4]>;
+
+fn parse_burrow<I>(input: I) -> Result<Burrow>
+where
+    I: Input,
+{
+    let mut lines = input.into_lines()?;
+    let mut burrow = Burrow::new();
+    for line in (&mut lines).skip(2) {
+        let line = line?;
+        let row: Vec<Amphipod> = line
+            .chars()
+            .filter_map(|c| match c {
+                'A' => Some(A),
+                'B' => Some(B),
+                'C' => Some(C),
+                'D' => Some(D),
+                _ => None,
+            })
+            .collect();
+        match row[..] {
+            [] => (),
+            [a, b, c, d] => burrow.push([a, b, c, d]),
+            _ => return Err(line.error("expected an amphipod in each of the four siderooms")),
+        }
+    }
+    if burrow.is_empty() {
+        return Err(lines.error("no amphipods in the burrow"));
+    }
+    Ok(burrow)
+}
+
+// the folded section of the diagram that gets inserted for part 2
+fn unfold(burrow: &[[Amphipod; 4]]) -> Burrow {
+    let mut unfolded = burrow.to_vec();
+    unfolded.splice(1..1, [[D, C, B, A], [D, B, A, C]]);
+    unfolded
+}
+
+fn organize(burrow: &[[Amphipod; 4]]) -> Result<u64> {
     let move_cost = HashMap::from([(A, 1), (B, 10), (C, 100), (D, 1000)]);
 
-    // no generic solution because I don't know what part 2 is gonna be like
+    let depth = burrow.len() as u64;
     let board = Board {
         hallway_len: 11,
         open_spots: [2, 4, 6, 8],
-        sideroom_depth: [2, 2, 2, 2],
+        sideroom_depth: [depth; 4],
     };
 
+    // siderooms are stacks, so the amphipod closest to the hallway goes last
     let mut state = State {
         hallway_pos: HashMap::new(),
-        sideroom_pop: [
-            VecDeque::from([B, D]),
-            VecDeque::from([C, C]),
-            VecDeque::from([D, A]),
-            VecDeque::from([A, B]),
-        ],
+        sideroom_pop: [0, 1, 2, 3].map(|idx| burrow.iter().rev().map(|row| row[idx]).collect()),
     };
 
     let mut dp = HashMap::new();
     let to_ret = get_min_cost(&mut dp, &move_cost, &board, &mut state);
+    debug!("{:?}", to_ret);
+    let min_cost =
+        to_ret.ok_or_else(|| Error::NoSolution("the amphipods can't be organized".to_string()))?;
     let mut state_iter = state;
     while let Some((cost, new_state)) = dp.remove(&state_iter) {
-        println!("{} {:?}", cost.unwrap(), state_iter);
+        trace!("{} {:?}", cost.unwrap(), state_iter);
         let blah = new_state;
         state_iter = blah.unwrap();
     }
-    to_ret.unwrap()
+    Ok(min_cost)
 }
 
-pub fn solution_2() -> u64 {
-    let move_cost = HashMap::from([(A, 1), (B, 10), (C, 100), (D, 1000)]);
+pub fn solution_1<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    organize(&parse_burrow(input)?)
+}
 
-    let board = Board {
-        hallway_len: 11,
-        open_spots: [2, 4, 6, 8],
-        sideroom_depth: [4, 4, 4, 4],
-    };
+pub fn solution_2<I>(input: I) -> Result<u64>
+where
+    I: Input,
+{
+    organize(&unfold(&parse_burrow(input)?))
+}
 
-    let mut state = State {
-        hallway_pos: HashMap::new(),
-        sideroom_pop: [
-            // VecDeque::from([A, D, D, B]),
-            // VecDeque::from([D, B, C, C]),
-            // VecDeque::from([C, A, B, B]),
-            // VecDeque::from([A, C, A, D]),
-            VecDeque::from([B, D, D, D]),
-            VecDeque::from([C, B, C, C]),
-            VecDeque::from([D, A, B, A]),
-            VecDeque::from([A, C, A, B]),
-        ],
-    };
+pub struct Day23;
 
-    let mut dp = HashMap::new();
-    let to_ret = get_min_cost(&mut dp, &move_cost, &board, &mut state);
-    println!("{:?}", to_ret);
-    let mut state_iter = state;
-    while let Some((cost, new_state)) = dp.remove(&state_iter) {
-        println!("{} {:?}", cost.unwrap(), state_iter);
-        let blah = new_state;
-        state_iter = blah.unwrap();
+impl Solution for Day23 {
+    type Puzzle = Burrow;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(&self, input: AnyLines) -> Result<Burrow> {
+        parse_burrow(input)
+    }
+
+    fn part_1(&self, burrow: &Burrow) -> Result<u64> {
+        organize(burrow)
+    }
+
+    fn part_2(&self, burrow: &Burrow) -> Result<u64> {
+        organize(&unfold(burrow))
     }
-    to_ret.unwrap()
 }
@@ -1,15 +1,21 @@
 use itertools::Itertools;
 use std::{
-    collections::{HashMap, VecDeque},
-    path::Path,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
 };
 
-use crate::util::read_lines;
+use crate::{
+    alu::{self, Instruction, Operand, Register, Registers},
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
-// hinges on a few properties in the input:
-// * A is 26 when B is negative, and 1 when B is positive
-// * when B is positive, B >= 10
-// * C <= 15 (specifically when B is positive), so (input + C) is always < 26
+// hinges on a few properties of the program, which `analyse` checks before this runs:
+// * every block is the same 18 instructions apart from three constants, A, B and C
+// * A is 1 or 26
+// * when A is 1, B >= 10
+// * 0 <= C <= 16, so (input + C) is always between 1 and 25
 // * A=1 and A=26 are balanced, and A=26 must always come after an A=1
 //
 // this means that when A = 1, Z' = Z * 26 + (input + C)
@@ -17,7 +23,7 @@ use crate::util::read_lines;
 //
 // As such, Z acts like a stack storing (input + C) values, and we need to make sure when we "pop" from the stack that:
 // input_curr + B_curr = input_prev + C_prev
-fn necessary_input(vars: &[(i64, i64, i64)], prefer_max: bool) -> String {
+fn necessary_input(vars: &[(i64, i64, i64)], prefer_max: bool) -> Result<String> {
     let mut stack: VecDeque<(usize, i64)> = VecDeque::new();
     let mut digits: HashMap<usize, i64> = HashMap::new();
 
@@ -25,7 +31,9 @@ fn necessary_input(vars: &[(i64, i64, i64)], prefer_max: bool) -> String {
         if a == 1 {
             stack.push_back((curr_idx, c));
         } else {
-            let (prev_idx, prev_offset) = stack.pop_back().unwrap();
+            let (prev_idx, prev_offset) = stack.pop_back().ok_or_else(|| {
+                Error::NoSolution(format!("block {} pops from an empty stack", curr_idx))
+            })?;
             let net_offset = prev_offset + b;
             let (prev_val, curr_val) = match (prefer_max, net_offset > 0) {
                 (true, true) => (9 - net_offset, 9),
@@ -33,117 +41,336 @@ fn necessary_input(vars: &[(i64, i64, i64)], prefer_max: bool) -> String {
                 (false, true) => (1, 1 + net_offset),
                 (false, false) => (1 - net_offset, 1),
             };
+            if !(1..=9).contains(&prev_val) || !(1..=9).contains(&curr_val) {
+                return Err(Error::NoSolution(format!(
+                    "blocks {} and {} can't be balanced with digits",
+                    prev_idx, curr_idx
+                )));
+            }
             digits.insert(prev_idx, prev_val);
             digits.insert(curr_idx, curr_val);
         }
     }
 
-    digits
+    if !stack.is_empty() {
+        return Err(Error::NoSolution(format!(
+            "{} blocks are never popped",
+            stack.len()
+        )));
+    }
+
+    Ok(digits
         .into_iter()
         .sorted()
-        .fold("".to_string(), |acc, (_, val)| acc + &val.to_string())
+        .fold("".to_string(), |acc, (_, val)| acc + &val.to_string()))
 }
 
-pub fn solution_1<P>(filename: P) -> String
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    let vars = lines
-        .into_iter()
-        .chunks(18)
-        .into_iter()
-        .map(|chunk| {
-            let vars = chunk
-                .into_iter()
-                .enumerate()
-                .filter_map(|(line_idx, l)| match line_idx {
-                    4 => Some((
-                        "A",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    5 => Some((
-                        "B",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    15 => Some((
-                        "C",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    _ => None,
-                })
-                .collect::<HashMap<_, _>>();
-            (vars["A"], vars["B"], vars["C"])
+// one block of MONAD, with 0 standing in for A, B and C
+const TEMPLATE: &str = "inp w
+mul x 0
+add x z
+mod x 26
+div z 0
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y";
+
+// where A, B and C are in the template
+const CONSTANTS: [usize; 3] = [4, 5, 15];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assumption {
+    Template,
+    Divisor,
+    AlwaysPushes,
+    SmallOffset,
+    Balanced,
+}
+
+// an assumption that doesn't hold, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub assumption: Assumption,
+    pub block: Option<usize>,
+    pub detail: String,
+}
+
+impl fmt::Display for Assumption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Assumption::Template => "every block matches the template",
+            Assumption::Divisor => "A is 1 or 26",
+            Assumption::AlwaysPushes => "B >= 10 when A is 1",
+            Assumption::SmallOffset => "0 <= C <= 16",
+            Assumption::Balanced => "every A=26 pops what an earlier A=1 pushed",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.block {
+            Some(block) => write!(f, "block {}: ", block)?,
+            None => write!(f, "program: ")?,
+        }
+        write!(f, "assumes {}, but {}", self.assumption, self.detail)
+    }
+}
+
+// the program split at each inp, after whatever comes before the first one
+fn blocks(program: &[Instruction]) -> (&[Instruction], Vec<&[Instruction]>) {
+    let starts: Vec<usize> = program
+        .iter()
+        .positions(|instruction| matches!(instruction, Instruction::Inp(_)))
+        .collect();
+    let prelude = &program[..starts.first().copied().unwrap_or(program.len())];
+    let blocks = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(program.len());
+            &program[start..end]
+        })
+        .collect();
+    (prelude, blocks)
+}
+
+// the constants of a block, if it matches the template
+fn block_vars(block: &[Instruction], template: &[Instruction]) -> Option<(i64, i64, i64)> {
+    if block.len() != template.len() {
+        return None;
+    }
+    let mut constants = [0; 3];
+    for (i, (instruction, expected)) in block.iter().zip(template).enumerate() {
+        let instruction = match (CONSTANTS.iter().position(|&at| at == i), *instruction) {
+            (Some(n), Instruction::Div(a, Operand::Number(constant))) => {
+                constants[n] = constant;
+                Instruction::Div(a, Operand::Number(0))
+            }
+            (Some(n), Instruction::Add(a, Operand::Number(constant))) => {
+                constants[n] = constant;
+                Instruction::Add(a, Operand::Number(0))
+            }
+            (_, instruction) => instruction,
+        };
+        if instruction != *expected {
+            return None;
+        }
+    }
+    Some((constants[0], constants[1], constants[2]))
+}
+
+// the (A, B, C) constants of each input block, or every assumption about them that
+// doesn't hold
+pub fn analyse(
+    program: &[Instruction],
+) -> std::result::Result<Vec<(i64, i64, i64)>, Vec<Violation>> {
+    let template = alu::parse_program(TEMPLATE).expect("the template is a valid program");
+    let mut violations = Vec::new();
+    let mut violation = |assumption, block, detail: String| {
+        violations.push(Violation {
+            assumption,
+            block,
+            detail,
         })
-        .collect_vec();
+    };
+
+    let (prelude, blocks) = blocks(program);
+    if !prelude.is_empty() {
+        violation(
+            Assumption::Template,
+            None,
+            format!("{} instructions come before the first inp", prelude.len()),
+        );
+    }
+    let mut vars = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        match block_vars(block, &template) {
+            Some(block_vars) => vars.push((i, block_vars)),
+            None => violation(
+                Assumption::Template,
+                Some(i),
+                format!("its {} instructions are different", block.len()),
+            ),
+        }
+    }
+    // the stack only makes sense if every block is one of MONAD's
+    let all_match = vars.len() == blocks.len();
+    let mut depth = 0;
+    for &(i, (a, b, c)) in &vars {
+        match a {
+            1 => depth += 1,
+            26 => depth -= 1,
+            _ => violation(Assumption::Divisor, Some(i), format!("A is {}", a)),
+        }
+        if a == 1 && b < 10 {
+            violation(Assumption::AlwaysPushes, Some(i), format!("B is {}", b));
+        }
+        if !(0..=16).contains(&c) {
+            violation(Assumption::SmallOffset, Some(i), format!("C is {}", c));
+        }
+        if all_match && depth < 0 {
+            violation(
+                Assumption::Balanced,
+                Some(i),
+                "it pops from an empty stack".to_string(),
+            );
+            depth = 0;
+        }
+    }
+    if all_match && depth > 0 {
+        violation(
+            Assumption::Balanced,
+            None,
+            format!("{} pushes are never popped", depth),
+        );
+    }
+
+    if violations.is_empty() {
+        let vars = vars.into_iter().map(|(_, block_vars)| block_vars).collect();
+        debug!("{:?}", vars);
+        Ok(vars)
+    } else {
+        Err(violations)
+    }
+}
+
+// tries every digit for every input, best first, skipping states that are already known
+// not to lead to z=0. slow, but makes no assumptions about what the program does
+pub fn search(program: &[Instruction], prefer_max: bool) -> Result<String> {
+    let (prelude, blocks) = blocks(program);
+    // only the registers a block depends on tell its states apart
+    let live: Vec<Vec<Register>> = (0..blocks.len())
+        .map(|i| alu::live_registers(&blocks[i..].concat(), &[Register::Z]))
+        .collect();
+    let digits: Vec<i64> = if prefer_max {
+        (1..=9).rev().collect()
+    } else {
+        (1..=9).collect()
+    };
+    let start = alu::run(prelude, []).map_err(|err| Error::NoSolution(err.to_string()))?;
 
-    println!("{:?}", vars);
-    necessary_input(&vars, true)
+    let mut search = Search {
+        blocks: &blocks,
+        live: &live,
+        digits: &digits,
+        dead_ends: HashSet::new(),
+        model_number: Vec::new(),
+    };
+    let found = search.find(0, start);
+    debug!("{} dead ends", search.dead_ends.len());
+    if found {
+        Ok(search.model_number.iter().join(""))
+    } else {
+        Err(Error::NoSolution(
+            "MONAD doesn't accept any model number".to_string(),
+        ))
+    }
+}
+
+struct Search<'a> {
+    blocks: &'a [&'a [Instruction]],
+    live: &'a [Vec<Register>],
+    digits: &'a [i64],
+    dead_ends: HashSet<(usize, Registers)>,
+    model_number: Vec<i64>,
 }
 
-pub fn solution_2<P>(filename: P) -> String
+impl<'a> Search<'a> {
+    fn find(&mut self, block: usize, registers: Registers) -> bool {
+        if block == self.blocks.len() {
+            return registers[Register::Z] == 0;
+        }
+        let mut state = Registers::default();
+        for register in &self.live[block] {
+            state.set(*register, registers[*register]);
+        }
+        if self.dead_ends.contains(&(block, state)) {
+            return false;
+        }
+        for &digit in self.digits {
+            // a digit that makes the block fail, e.g. by dividing by zero, isn't valid
+            if let Ok(next) = alu::run_from(registers, self.blocks[block], [digit]) {
+                self.model_number.push(digit);
+                if self.find(block + 1, next) {
+                    return true;
+                }
+                self.model_number.pop();
+            }
+        }
+        self.dead_ends.insert((block, state));
+        false
+    }
+}
+
+// runs MONAD over the model number to make sure it's really accepted
+fn check_model_number(program: &[Instruction], model_number: String) -> Result<String> {
+    let digits = model_number.bytes().map(|digit| (digit - b'0') as i64);
+    let registers = alu::run(program, digits).map_err(|err| Error::NoSolution(err.to_string()))?;
+    if registers[Register::Z] != 0 {
+        return Err(Error::NoSolution(format!(
+            "MONAD rejects {} with z={}",
+            model_number,
+            registers[Register::Z]
+        )));
+    }
+    Ok(model_number)
+}
+
+fn model_number(program: &[Instruction], prefer_max: bool) -> Result<String> {
+    match analyse(program) {
+        Ok(vars) => check_model_number(program, necessary_input(&vars, prefer_max)?),
+        Err(violations) => {
+            for violation in &violations {
+                info!("{}", violation);
+            }
+            info!("falling back to searching every model number");
+            search(program, prefer_max)
+        }
+    }
+}
+
+pub fn solution_1<I>(input: I) -> Result<String>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let vars = lines
-        .into_iter()
-        .chunks(18)
-        .into_iter()
-        .map(|chunk| {
-            let vars = chunk
-                .into_iter()
-                .enumerate()
-                .filter_map(|(line_idx, l)| match line_idx {
-                    4 => Some((
-                        "A",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    5 => Some((
-                        "B",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    15 => Some((
-                        "C",
-                        l.unwrap()
-                            .split_whitespace()
-                            .nth(2)
-                            .unwrap()
-                            .parse::<i64>()
-                            .unwrap(),
-                    )),
-                    _ => None,
-                })
-                .collect::<HashMap<_, _>>();
-            (vars["A"], vars["B"], vars["C"])
-        })
-        .collect_vec();
+    model_number(&alu::parse_program(input)?, true)
+}
+
+pub fn solution_2<I>(input: I) -> Result<String>
+where
+    I: Input,
+{
+    model_number(&alu::parse_program(input)?, false)
+}
+
+pub struct Day24;
 
-    println!("{:?}", vars);
-    necessary_input(&vars, false)
+impl Solution for Day24 {
+    type Puzzle = Vec<Instruction>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<Instruction>> {
+        alu::parse_program(input)
+    }
+
+    fn part_1(&self, program: &Vec<Instruction>) -> Result<String> {
+        model_number(program, true)
+    }
+
+    fn part_2(&self, program: &Vec<Instruction>) -> Result<String> {
+        model_number(program, false)
+    }
 }
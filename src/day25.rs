@@ -1,93 +1,136 @@
-use std::{cmp::max, collections::HashSet, path::Path};
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    render::Shade,
+    solution::Solution,
+    util::{parse_chars, Grid},
+};
 
-use itertools::Itertools;
-
-use crate::util::read_lines;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeaFloor {
+    Empty,
+    East,
+    South,
+}
 
-fn step(
-    max_row: usize,
-    max_col: usize,
-    east_facing: &mut HashSet<(usize, usize)>,
-    south_facing: &mut HashSet<(usize, usize)>,
-) -> bool {
-    let mut changed = false;
+impl Shade for SeaFloor {
+    fn glyph(&self) -> char {
+        match self {
+            SeaFloor::Empty => '.',
+            SeaFloor::East => '>',
+            SeaFloor::South => 'v',
+        }
+    }
 
-    let next_east_facing: HashSet<_> = east_facing
-        .iter()
-        .cloned()
-        .map(|(r, c)| {
-            let next_pos = (r, (c + 1) % max_col);
-            if east_facing.contains(&next_pos) || south_facing.contains(&next_pos) {
-                (r, c)
-            } else {
-                changed = true;
-                next_pos
-            }
-        })
-        .collect();
-    east_facing.clone_from(&next_east_facing);
+    fn shade(&self) -> u8 {
+        match self {
+            SeaFloor::Empty => 0,
+            SeaFloor::East => 255,
+            SeaFloor::South => 128,
+        }
+    }
+}
 
-    let next_south_facing: HashSet<_> = south_facing
+// moves every cucumber of `herd` that has room one step along `direction`, all at once
+fn move_herd(herds: &mut Grid<SeaFloor>, herd: SeaFloor, direction: (isize, isize)) -> bool {
+    let moving: Vec<_> = herds
         .iter()
-        .cloned()
-        .map(|(r, c)| {
-            let next_pos = ((r + 1) % max_row, c);
-            if east_facing.contains(&next_pos) || south_facing.contains(&next_pos) {
-                (r, c)
-            } else {
-                changed = true;
-                next_pos
-            }
+        .filter(|&(coord, cell)| {
+            *cell == herd && herds[herds.wrapping_offset(coord, direction)] == SeaFloor::Empty
         })
+        .map(|(coord, _)| coord)
         .collect();
-    south_facing.clone_from(&next_south_facing);
+    for &coord in &moving {
+        herds[coord] = SeaFloor::Empty;
+        let next_pos = herds.wrapping_offset(coord, direction);
+        herds[next_pos] = herd;
+    }
+    !moving.is_empty()
+}
 
-    changed
+fn step(herds: &mut Grid<SeaFloor>) -> bool {
+    let east_moved = move_herd(herds, SeaFloor::East, (0, 1));
+    let south_moved = move_herd(herds, SeaFloor::South, (1, 0));
+    east_moved || south_moved
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+fn parse_herds<I>(input: I) -> Result<Grid<SeaFloor>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-
-    let mut max_row = 0;
-    let mut max_col = 0;
-    let grid_vals: Vec<_> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row, line)| {
-            max_row = max(max_row, row + 1);
-            line.unwrap()
-                .chars()
-                .enumerate()
-                .map(|(col, c)| {
-                    max_col = max(max_col, col + 1);
-                    ((row, col), c)
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect_vec();
-    let mut east_facing: HashSet<_> = grid_vals
-        .iter()
-        .filter_map(|(coord, c)| match c {
-            '>' => Some(*coord),
-            _ => None,
-        })
-        .collect();
-    let mut south_facing: HashSet<_> = grid_vals
-        .iter()
-        .filter_map(|(coord, c)| match c {
-            'v' => Some(*coord),
-            _ => None,
-        })
-        .collect();
+    parse_chars(input, |c| match c {
+        '>' => Some(SeaFloor::East),
+        'v' => Some(SeaFloor::South),
+        '.' => Some(SeaFloor::Empty),
+        _ => None,
+    })
+}
 
+fn first_stuck_step(herds: &Grid<SeaFloor>) -> usize {
+    let mut herds = herds.clone();
     for i in 1.. {
-        if !step(max_row, max_col, &mut east_facing, &mut south_facing) {
+        if !step(&mut herds) {
             return i;
         }
     }
     unreachable!()
 }
+
+// the herds after `steps` steps, for rendering
+pub fn herds_after<I>(input: I, steps: usize) -> Result<Grid<SeaFloor>>
+where
+    I: Input,
+{
+    let mut herds = parse_herds(input)?;
+    for _ in 0..steps {
+        step(&mut herds);
+    }
+    Ok(herds)
+}
+
+// every generation up to `limit` steps, or until the herds are stuck if there's no limit,
+// for animating
+pub fn generations<I>(input: I, limit: Option<usize>) -> Result<Vec<Grid<SeaFloor>>>
+where
+    I: Input,
+{
+    let mut herds = parse_herds(input)?;
+    let mut frames = vec![herds.clone()];
+    while limit.is_none_or(|limit| frames.len() <= limit) {
+        let moved = step(&mut herds);
+        if limit.is_none() && !moved {
+            break;
+        }
+        frames.push(herds.clone());
+    }
+    Ok(frames)
+}
+
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(first_stuck_step(&parse_herds(input)?))
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Puzzle = Grid<SeaFloor>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    const PARTS: u32 = 1;
+
+    fn parse(&self, input: AnyLines) -> Result<Grid<SeaFloor>> {
+        parse_herds(input)
+    }
+
+    fn part_1(&self, herds: &Grid<SeaFloor>) -> Result<usize> {
+        Ok(first_stuck_step(herds))
+    }
+
+    fn part_2(&self, _: &Grid<SeaFloor>) -> Result<usize> {
+        Err(Error::NoSuchPart(2))
+    }
+}
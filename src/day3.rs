@@ -1,36 +1,54 @@
-use std::{path::Path, vec};
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
-use crate::util::read_lines;
-
-pub fn solution_1<P>(fname: P) -> i32
+fn parse_bits<I>(input: I) -> Result<Vec<Vec<u8>>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(fname).expect("failed to read input");
-    let nums: Vec<String> = lines
-        .into_iter()
-        .map(|line| line.expect("couldn't find line"))
-        .collect();
+    let mut lines = input.into_lines()?;
+    let first = lines.next_line()?;
+    let width = first.len();
+    std::iter::once(Ok(first))
+        .chain(lines)
+        .map(|line| {
+            let line = line?;
+            if line.len() != width {
+                return Err(line.error(format!("expected {} bits", width)));
+            }
+            line.chars()
+                .map(|c| match c {
+                    '0' => Ok(0),
+                    '1' => Ok(1),
+                    _ => Err(line.error(format!("bad bit: {:?}", c))),
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect()
+}
+
+fn power_consumption(nums: &[Vec<u8>]) -> Result<i32> {
     let num_nums = nums.len();
     let mut one_counts = vec![0; nums[0].len()];
-    nums.into_iter()
-        .map(|bin| bin.chars().collect::<Vec<_>>())
-        .for_each(|f| {
-            f.into_iter().enumerate().for_each(|(idx, bit)| match bit {
-                '0' => (),
-                '1' => {
-                    one_counts[idx] += 1;
-                }
-                _ => panic!("bad bit: {:?}", bit),
-            })
-        });
+    nums.iter().for_each(|f| {
+        f.iter()
+            .enumerate()
+            .for_each(|(idx, bit)| one_counts[idx] += *bit as usize)
+    });
 
     let mut gamma_rate = 0;
     let mut epsilon_rate = 0;
     for (idx, count) in one_counts.into_iter().rev().enumerate() {
         match count {
-            a if a == (num_nums / 2) => panic!("no tiebreaker logic"),
-            a if a > (num_nums / 2) => {
+            a if 2 * a == num_nums => {
+                return Err(Error::NoSolution(format!(
+                    "bit {} is tied and there's no tiebreaker logic",
+                    idx
+                )))
+            }
+            a if 2 * a > num_nums => {
                 gamma_rate += 1 << idx;
             }
             _ => {
@@ -38,7 +56,14 @@ where
             }
         }
     }
-    gamma_rate * epsilon_rate
+    Ok(gamma_rate * epsilon_rate)
+}
+
+pub fn solution_1<I>(input: I) -> Result<i32>
+where
+    I: Input,
+{
+    power_consumption(&parse_bits(input)?)
 }
 
 enum Rating {
@@ -56,10 +81,16 @@ fn vec_to_usize(bits: &[u8]) -> usize {
     to_ret
 }
 
-fn filter_step(nums: Vec<Vec<u8>>, idx: usize, rating: Rating) -> usize {
+fn filter_step(nums: Vec<Vec<u8>>, idx: usize, rating: Rating) -> Result<usize> {
     let num_nums = nums.len();
     if num_nums == 1 {
-        return vec_to_usize(&nums[0]);
+        return Ok(vec_to_usize(&nums[0]));
+    }
+    if idx == nums[0].len() {
+        return Err(Error::NoSolution(format!(
+            "{} numbers are left after filtering every bit",
+            num_nums
+        )));
     }
     let num_ones: usize = nums.iter().map(|v| v[idx] as usize).sum();
     let bit_to_filter: u8 = match num_ones {
@@ -77,34 +108,45 @@ fn filter_step(nums: Vec<Vec<u8>>, idx: usize, rating: Rating) -> usize {
         },
         _ => panic!("ranges should be all complete"),
     };
-    filter_step(
-        nums.into_iter()
-            .filter(|num| num[idx] == bit_to_filter)
-            .collect(),
-        idx + 1,
-        rating,
-    )
+    let filtered: Vec<_> = nums
+        .iter()
+        .filter(|num| num[idx] == bit_to_filter)
+        .cloned()
+        .collect();
+    // every number left has the same bit here, so there's nothing to filter on
+    let nums = if filtered.is_empty() { nums } else { filtered };
+    filter_step(nums, idx + 1, rating)
 }
 
-pub fn solution_2<P>(fname: P) -> usize
+fn life_support_rating(nums: &[Vec<u8>]) -> Result<usize> {
+    let oxygen = filter_step(nums.to_vec(), 0, Rating::Oxygen)?;
+    let co2 = filter_step(nums.to_vec(), 0, Rating::CO2)?;
+    Ok(oxygen * co2)
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(fname).expect("failed to read input");
-    let nums: Vec<Vec<u8>> = lines
-        .into_iter()
-        .map(|line| {
-            line.expect("couldn't find line")
-                .chars()
-                .map(|c| match c {
-                    '0' => 0,
-                    '1' => 1,
-                    _ => panic!("bad bit: {:?}", c),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let oxygen = filter_step(nums.clone(), 0, Rating::Oxygen);
-    let co2 = filter_step(nums, 0, Rating::CO2);
-    oxygen * co2
+    life_support_rating(&parse_bits(input)?)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Puzzle = Vec<Vec<u8>>;
+    type Answer1 = i32;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<Vec<u8>>> {
+        parse_bits(input)
+    }
+
+    fn part_1(&self, nums: &Vec<Vec<u8>>) -> Result<i32> {
+        power_consumption(nums)
+    }
+
+    fn part_2(&self, nums: &Vec<Vec<u8>>) -> Result<usize> {
+        life_support_rating(nums)
+    }
 }
@@ -1,22 +1,23 @@
-use std::{cmp, collections::HashMap, path::Path};
+use std::{cmp, collections::HashMap};
 
-use itertools::Itertools;
-
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 const BINGO_SIZE: usize = 5;
 
 #[derive(Debug)]
-struct BingoCard {
+pub struct BingoCard {
     // (row, col) -> (number, order)
     nums: HashMap<(usize, usize), (i32, usize)>,
 }
 
-fn get_order_and_score(card: BingoCard) -> (usize, i32) {
+fn get_order_and_score(card: &BingoCard) -> (usize, i32) {
     // --- get order and value of number that wins
     // find max(row) and max(col) for all rows and cols, then take min of that
     let min_rows = (0..(BINGO_SIZE - 1))
-        .into_iter()
         .map(|curr_row| {
             card.nums
                 .iter()
@@ -34,7 +35,6 @@ fn get_order_and_score(card: BingoCard) -> (usize, i32) {
         .unwrap();
 
     let min_cols = (0..(BINGO_SIZE - 1))
-        .into_iter()
         .map(|curr_col| {
             card.nums
                 .iter()
@@ -52,9 +52,10 @@ fn get_order_and_score(card: BingoCard) -> (usize, i32) {
         .unwrap();
 
     let (winner_order, winner_value) = cmp::min(min_rows, min_cols);
-    println!(
+    trace!(
         "winner order: {}, winner_value: {}",
-        winner_order, winner_value
+        winner_order,
+        winner_value
     );
 
     // --- find unmarked numbers
@@ -69,70 +70,94 @@ fn get_order_and_score(card: BingoCard) -> (usize, i32) {
             }
         })
         .sum();
-    println!("unmarked_numbers: {}", unmarked_numbers);
+    trace!("unmarked_numbers: {}", unmarked_numbers);
 
     (*winner_order, unmarked_numbers * winner_value)
 }
 
-fn get_card_info<P>(filename: P) -> Vec<BingoCard>
+fn get_card_info<I>(input: I) -> Result<Vec<BingoCard>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    let mut it = lines;
-    let number_to_order: HashMap<i32, usize> = it
-        .next()
-        .unwrap()
-        .unwrap()
+    let mut lines = input.into_lines()?;
+    let draws = lines.next_line()?;
+    let number_to_order: HashMap<i32, usize> = draws
         .split(',')
-        .map(|num| num.parse::<i32>().unwrap())
         .enumerate()
-        .map(|(idx, draw)| (draw, idx))
-        .collect();
-
-    println!("{:?}", number_to_order);
-    it.chunks(BINGO_SIZE + 1)
-        .into_iter()
-        .map(|mut chunk| {
-            let _ = chunk.next().unwrap();
-            let nums: HashMap<(usize, usize), (i32, usize)> = chunk
-                .into_iter()
-                .enumerate()
-                .map(|(row_idx, row)| {
-                    row.unwrap()
-                        .split_whitespace()
-                        .map(|s| s.parse::<i32>().unwrap())
-                        .enumerate()
-                        .map(|(col_idx, num)| ((row_idx, col_idx), (num, number_to_order[&num])))
-                        .collect::<Vec<_>>()
-                })
-                .flatten()
-                .collect();
-            BingoCard { nums }
-        })
-        .collect()
+        .map(|(idx, draw)| Ok((draws.parse_field::<i32>(draw)?, idx)))
+        .collect::<Result<_>>()?;
+
+    debug!("{:?}", number_to_order);
+    let mut cards = Vec::new();
+    // every card is preceded by a blank line
+    while let Some(blank) = lines.next() {
+        let blank = blank?;
+        if !blank.trim().is_empty() {
+            return Err(blank.error("expected a blank line between cards"));
+        }
+        let mut nums: HashMap<(usize, usize), (i32, usize)> = HashMap::new();
+        for row_idx in 0..BINGO_SIZE {
+            let row = lines.next_line()?;
+            let row_nums = row
+                .split_whitespace()
+                .map(|s| row.parse_field::<i32>(s))
+                .collect::<Result<Vec<_>>>()?;
+            if row_nums.len() != BINGO_SIZE {
+                return Err(row.error(format!("expected {} numbers", BINGO_SIZE)));
+            }
+            for (col_idx, num) in row_nums.into_iter().enumerate() {
+                let order = *number_to_order
+                    .get(&num)
+                    .ok_or_else(|| row.error(format!("{} is never drawn", num)))?;
+                nums.insert((row_idx, col_idx), (num, order));
+            }
+        }
+        cards.push(BingoCard { nums });
+    }
+    if cards.is_empty() {
+        return Err(lines.error("no bingo cards"));
+    }
+    Ok(cards)
+}
+
+fn first_winner_score(cards: &[BingoCard]) -> i32 {
+    cards.iter().map(get_order_and_score).min().unwrap().1
 }
 
-pub fn solution_1<P>(fname: P) -> i32
+fn last_winner_score(cards: &[BingoCard]) -> i32 {
+    cards.iter().map(get_order_and_score).max().unwrap().1
+}
+
+pub fn solution_1<I>(input: I) -> Result<i32>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    get_card_info(fname)
-        .into_iter()
-        .map(get_order_and_score)
-        .min()
-        .unwrap()
-        .1
+    Ok(first_winner_score(&get_card_info(input)?))
 }
 
-pub fn solution_2<P>(fname: P) -> i32
+pub fn solution_2<I>(input: I) -> Result<i32>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    get_card_info(fname)
-        .into_iter()
-        .map(get_order_and_score)
-        .max()
-        .unwrap()
-        .1
+    Ok(last_winner_score(&get_card_info(input)?))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Puzzle = Vec<BingoCard>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<BingoCard>> {
+        get_card_info(input)
+    }
+
+    fn part_1(&self, cards: &Vec<BingoCard>) -> Result<i32> {
+        Ok(first_winner_score(cards))
+    }
+
+    fn part_2(&self, cards: &Vec<BingoCard>) -> Result<i32> {
+        Ok(last_winner_score(cards))
+    }
 }
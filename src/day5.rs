@@ -1,30 +1,45 @@
-use std::{collections::HashMap, path::Path};
+use std::collections::HashMap;
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
-pub fn solution_1<P>(filename: P) -> usize
+type Segment = ((i32, i32), (i32, i32));
+
+fn parse_segments<I>(input: I) -> Result<Vec<Segment>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    lines
-        .into_iter()
+    input
+        .into_lines()?
         .map(|line| {
-            line.expect("couldn't find line")
-                .split_terminator(" -> ")
+            let line = line?;
+            line.split_terminator(" -> ")
                 .map(|coord| {
                     coord
                         .split_terminator(',')
-                        .map(|s| s.parse::<i32>().unwrap())
+                        .map(|s| line.parse_field::<i32>(s))
+                        .collect::<Result<Vec<_>>>()?
+                        .into_iter()
                         .collect_tuple::<(i32, i32)>()
+                        .ok_or_else(|| line.error(format!("bad coordinate {:?}", coord)))
                 })
-                .map(|coord| coord.unwrap())
-                .collect_tuple::<((i32, i32), (i32, i32))>()
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .collect_tuple::<Segment>()
+                .ok_or_else(|| line.error("expected two coordinates"))
         })
-        .map(|coord_pair| coord_pair.unwrap())
-        .filter_map(|((lx, ly), (rx, ry))| {
+        .collect()
+}
+
+fn straight_overlaps(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .filter_map(|&((lx, ly), (rx, ry))| {
             if lx != rx && ly != ry {
                 None
             } else if lx == rx {
@@ -53,27 +68,10 @@ where
         .count()
 }
 
-pub fn solution_2<P>(filename: P) -> usize
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    lines
-        .into_iter()
-        .map(|line| {
-            line.expect("couldn't find line")
-                .split_terminator(" -> ")
-                .map(|coord| {
-                    coord
-                        .split_terminator(',')
-                        .map(|s| s.parse::<i32>().unwrap())
-                        .collect_tuple::<(i32, i32)>()
-                })
-                .map(|coord| coord.unwrap())
-                .collect_tuple::<((i32, i32), (i32, i32))>()
-        })
-        .map(|coord_pair| coord_pair.unwrap())
-        .map(|((lx, ly), (rx, ry))| {
+fn all_overlaps(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .flat_map(|&((lx, ly), (rx, ry))| {
             if lx != rx && ly != ry {
                 let itx = if lx < rx { 1 } else { -1 };
                 let ity = if ly < ry { 1 } else { -1 };
@@ -93,7 +91,6 @@ where
                     .collect::<Vec<(i32, i32)>>()
             }
         })
-        .flatten()
         .fold(HashMap::<(i32, i32), i32>::new(), |mut counts, coord| {
             *(counts.entry(coord).or_insert(0)) += 1;
             counts
@@ -102,3 +99,37 @@ where
         .filter(|(_, count)| *count > 1)
         .count()
 }
+
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(straight_overlaps(&parse_segments(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(all_overlaps(&parse_segments(input)?))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Puzzle = Vec<Segment>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<Segment>> {
+        parse_segments(input)
+    }
+
+    fn part_1(&self, segments: &Vec<Segment>) -> Result<usize> {
+        Ok(straight_overlaps(segments))
+    }
+
+    fn part_2(&self, segments: &Vec<Segment>) -> Result<usize> {
+        Ok(all_overlaps(segments))
+    }
+}
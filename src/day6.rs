@@ -1,6 +1,10 @@
-use std::{collections::HashMap, path::Path};
+use std::collections::HashMap;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
 fn generated_fish(dp: &mut HashMap<(u32, u32), usize>, timer: u32, num_days: u32) -> usize {
     if num_days == 0 {
@@ -17,28 +21,57 @@ fn generated_fish(dp: &mut HashMap<(u32, u32), usize>, timer: u32, num_days: u32
     generated_fish(dp, timer - 1, num_days - 1)
 }
 
-pub fn solution_1<P>(filename: P) -> usize
+fn parse_timers<I>(input: I) -> Result<Vec<u32>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    const NUM_DAYS: u32 = 80;
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let line = lines.next().expect("no line").expect("no line 2");
-    let mut dp: HashMap<(u32, u32), usize> = HashMap::new();
+    let line = input.into_lines()?.next_line()?;
     line.split_terminator(',')
-        .map(|timer| generated_fish(&mut dp, timer.parse::<u32>().unwrap(), NUM_DAYS))
+        .map(|timer| match line.parse_field::<u32>(timer)? {
+            timer if timer <= 8 => Ok(timer),
+            timer => Err(line.error(format!("timer {} is above 8", timer))),
+        })
+        .collect()
+}
+
+fn total_fish(timers: &[u32], num_days: u32) -> usize {
+    let mut dp: HashMap<(u32, u32), usize> = HashMap::new();
+    timers
+        .iter()
+        .map(|timer| generated_fish(&mut dp, *timer, num_days))
         .sum()
 }
 
-pub fn solution_2<P>(filename: P) -> usize
+pub fn solution_1<I>(input: I) -> Result<usize>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    const NUM_DAYS: u32 = 256;
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let line = lines.next().expect("no line").expect("no line 2");
-    let mut dp: HashMap<(u32, u32), usize> = HashMap::new();
-    line.split_terminator(',')
-        .map(|timer| generated_fish(&mut dp, timer.parse::<u32>().unwrap(), NUM_DAYS))
-        .sum()
+    Ok(total_fish(&parse_timers(input)?, 80))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(total_fish(&parse_timers(input)?, 256))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Puzzle = Vec<u32>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<u32>> {
+        parse_timers(input)
+    }
+
+    fn part_1(&self, timers: &Vec<u32>) -> Result<usize> {
+        Ok(total_fish(timers, 80))
+    }
+
+    fn part_2(&self, timers: &Vec<u32>) -> Result<usize> {
+        Ok(total_fish(timers, 256))
+    }
 }
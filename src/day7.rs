@@ -1,38 +1,37 @@
-use std::path::Path;
-
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input},
+    solution::Solution,
+};
 
-pub fn solution_1<P>(filename: P) -> i32
+fn parse_positions<I>(input: I) -> Result<Vec<i32>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let line = lines.next().expect("no line").expect("no line 2");
-    let positions: Vec<i32> = line
+    let line = input.into_lines()?.next_line()?;
+    let positions = line
         .split_terminator(',')
-        .map(|pos| pos.parse::<i32>().unwrap())
-        .sorted()
-        .collect();
+        .map(|pos| line.parse_field::<i32>(pos))
+        .collect::<Result<Vec<_>>>()?;
+    if positions.is_empty() {
+        return Err(line.error("no crab positions"));
+    }
+    Ok(positions.into_iter().sorted().collect())
+}
+
+// positions must be sorted
+fn linear_fuel(positions: &[i32]) -> i32 {
     let opt_pos = positions[positions.len() / 2];
-    positions.into_iter().map(|pos| (pos - opt_pos).abs()).sum()
+    positions.iter().map(|pos| (pos - opt_pos).abs()).sum()
 }
 
-pub fn solution_2<P>(filename: P) -> i32
-where
-    P: AsRef<Path>,
-{
-    let mut lines = read_lines(filename).expect("failed to read input");
-    let line = lines.next().expect("no line").expect("no line 2");
-    let positions: Vec<i32> = line
-        .split_terminator(',')
-        .map(|pos| pos.parse::<i32>().unwrap())
-        .sorted()
-        .collect();
+// positions must be sorted
+fn triangular_fuel(positions: &[i32]) -> i32 {
     let first_pos = *positions.first().unwrap();
     let last_pos = *positions.last().unwrap();
-    (first_pos..last_pos)
+    (first_pos..=last_pos)
         .map(|pot_pos| {
             positions
                 .iter()
@@ -45,3 +44,37 @@ where
         .min()
         .unwrap()
 }
+
+pub fn solution_1<I>(input: I) -> Result<i32>
+where
+    I: Input,
+{
+    Ok(linear_fuel(&parse_positions(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<i32>
+where
+    I: Input,
+{
+    Ok(triangular_fuel(&parse_positions(input)?))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Puzzle = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<i32>> {
+        parse_positions(input)
+    }
+
+    fn part_1(&self, positions: &Vec<i32>) -> Result<i32> {
+        Ok(linear_fuel(positions))
+    }
+
+    fn part_2(&self, positions: &Vec<i32>) -> Result<i32> {
+        Ok(triangular_fuel(positions))
+    }
+}
@@ -1,36 +1,75 @@
-use std::{
-    char::from_digit,
-    collections::{HashMap, HashSet},
-    path::Path,
-};
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::Result,
+    input::{AnyLines, Input, Line},
+    solution::Solution,
+};
+
+// one line of notes: the ten patterns and the four outputs, kept with the line so a
+// wiring that can't be worked out can point at it
+pub struct Entry {
+    line: Line,
+    patterns: Vec<String>,
+    outputs: Vec<String>,
+}
 
-pub fn solution_1<P>(filename: P) -> usize
+fn parse_entries<I>(input: I) -> Result<Vec<Entry>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let unique_digits = HashSet::from([2, 3, 4, 7]);
-    let lines = read_lines(filename).expect("failed to read input");
-    lines
-        .into_iter()
+    input
+        .into_lines()?
         .map(|line| {
-            line.unwrap()
+            let line = line?;
+            let (patterns, outputs) = line
                 .split_terminator(" | ")
-                .nth(1)
-                .unwrap()
-                .split_whitespace()
-                .map(|digit| digit.len())
-                .collect::<Vec<usize>>()
+                .map(|part| {
+                    part.split_whitespace()
+                        .map(|s| s.to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect_tuple()
+                .ok_or_else(|| line.error("expected patterns and outputs split by \" | \""))?;
+            if patterns.len() != 10 || outputs.len() != 4 {
+                return Err(line.error("expected 10 patterns and 4 outputs"));
+            }
+            if let Some(c) = patterns
+                .iter()
+                .chain(outputs.iter())
+                .flat_map(|pattern| pattern.chars())
+                .find(|c| !('a'..='g').contains(c))
+            {
+                return Err(line.error(format!("unknown segment {:?}", c)));
+            }
+            Ok(Entry {
+                line,
+                patterns,
+                outputs,
+            })
         })
-        .flatten()
+        .collect()
+}
+
+fn count_unique_outputs(entries: &[Entry]) -> usize {
+    let unique_digits = HashSet::from([2, 3, 4, 7]);
+    entries
+        .iter()
+        .flat_map(|entry| entry.outputs.iter().map(|digit| digit.len()))
         .filter(|unique_lens| unique_digits.contains(unique_lens))
         .count()
 }
 
-fn find_digits(digits: Vec<String>) -> HashMap<String, char> {
+pub fn solution_1<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    Ok(count_unique_outputs(&parse_entries(input)?))
+}
+
+fn find_digits(entry: &Entry) -> Result<HashMap<String, u32>> {
     // 1, 4, 7, 8 -> by length
     // we know a, (c/f), (b/d), (e/g)
     // 0 -> by having a, (c/f), (e/g)
@@ -39,28 +78,38 @@ fn find_digits(digits: Vec<String>) -> HashMap<String, char> {
     // we know a, b, d, c, f, (e/g)
     // 9 -> by having a, (b/d), (c/f)
     // we know a, b, d, c, f, e, g
-    let mut digit_letters: HashMap<u8, HashSet<char>> = HashMap::new();
-    for digit in digits.iter() {
+    let line = &entry.line;
+    let mut digit_letters: HashMap<u32, HashSet<char>> = HashMap::new();
+    for digit in entry.patterns.iter() {
         match digit.len() {
             2 => {
-                digit_letters.insert(1, digit.chars().into_iter().collect());
+                digit_letters.insert(1, digit.chars().collect());
             }
             3 => {
-                digit_letters.insert(7, digit.chars().into_iter().collect());
+                digit_letters.insert(7, digit.chars().collect());
             }
             4 => {
-                digit_letters.insert(4, digit.chars().into_iter().collect());
+                digit_letters.insert(4, digit.chars().collect());
             }
             7 => {
-                digit_letters.insert(8, digit.chars().into_iter().collect());
+                digit_letters.insert(8, digit.chars().collect());
             }
-            _ => (),
+            5 | 6 => (),
+            _ => return Err(line.error(format!("no digit has the pattern {:?}", digit))),
         }
     }
-    let letter_a = digit_letters[&7]
+    for (digit, len) in [(1, 2), (7, 3), (4, 4), (8, 7)] {
+        if !digit_letters.contains_key(&digit) {
+            return Err(line.error(format!(
+                "no pattern with {} segments for the digit {}",
+                len, digit
+            )));
+        }
+    }
+    let letter_a = *digit_letters[&7]
         .difference(&digit_letters[&1])
-        .next()
-        .unwrap();
+        .exactly_one()
+        .map_err(|_| line.error("the patterns for 1 and 7 don't differ by one segment"))?;
     let c_or_f = digit_letters[&1].clone();
     let b_or_d: HashSet<char> = digit_letters[&4]
         .difference(&digit_letters[&1])
@@ -70,102 +119,98 @@ fn find_digits(digits: Vec<String>) -> HashMap<String, char> {
         .difference(&digit_letters[&4])
         .copied()
         .collect();
-    e_or_g.remove(letter_a);
-    let mut letter_b: char = 'x';
-    let mut letter_e: char = 'x';
-    for digit in digits.iter() {
-        match digit.len() {
-            2 | 3 | 4 | 7 => (),
-            6 => {
-                let chars: HashSet<char> = digit.chars().into_iter().collect();
-                let missing = digit_letters[&8].difference(&chars).next().unwrap();
-                if e_or_g.contains(missing) {
-                    letter_e = *missing;
-                    digit_letters.insert(9, chars);
-                } else if b_or_d.contains(missing) {
-                    letter_b = *b_or_d.iter().find(|c| *c != missing).unwrap();
-                    digit_letters.insert(0, chars);
-                } else if c_or_f.contains(missing) {
-                    digit_letters.insert(6, chars);
-                } else {
-                    unreachable!()
-                }
-            }
-            _ => (),
+    e_or_g.remove(&letter_a);
+    let mut letter_b = None;
+    let mut letter_e = None;
+    for digit in entry.patterns.iter().filter(|digit| digit.len() == 6) {
+        let chars: HashSet<char> = digit.chars().collect();
+        let missing = digit_letters[&8]
+            .difference(&chars)
+            .exactly_one()
+            .map_err(|_| line.error(format!("{:?} isn't 8 less one segment", digit)))?;
+        if e_or_g.contains(missing) {
+            letter_e = Some(*missing);
+            digit_letters.insert(9, chars);
+        } else if b_or_d.contains(missing) {
+            letter_b = b_or_d.iter().find(|c| *c != missing).copied();
+            digit_letters.insert(0, chars);
+        } else if c_or_f.contains(missing) {
+            digit_letters.insert(6, chars);
+        } else {
+            return Err(line.error(format!("{:?} can't be 0, 6 or 9", digit)));
         }
     }
-    for digit in digits.iter() {
-        match digit.len() {
-            2 | 3 | 4 | 7 | 6 => (),
-            5 => {
-                let chars: HashSet<char> = digit.chars().into_iter().collect();
-                let has_b = chars.contains(&letter_b);
-                let has_e = chars.contains(&letter_e);
-                match (has_b, has_e) {
-                    (false, false) => {
-                        digit_letters.insert(3, chars);
-                    }
-                    (false, true) => {
-                        digit_letters.insert(2, chars);
-                    }
-                    (true, false) => {
-                        digit_letters.insert(5, chars);
-                    }
-                    _ => unreachable!(),
-                }
+    let (letter_b, letter_e) = letter_b
+        .zip(letter_e)
+        .ok_or_else(|| line.error("the six segment patterns aren't 0, 6 and 9"))?;
+    for digit in entry.patterns.iter().filter(|digit| digit.len() == 5) {
+        let chars: HashSet<char> = digit.chars().collect();
+        let has_b = chars.contains(&letter_b);
+        let has_e = chars.contains(&letter_e);
+        match (has_b, has_e) {
+            (false, false) => {
+                digit_letters.insert(3, chars);
             }
-            _ => unreachable!(),
+            (false, true) => {
+                digit_letters.insert(2, chars);
+            }
+            (true, false) => {
+                digit_letters.insert(5, chars);
+            }
+            (true, true) => return Err(line.error(format!("{:?} can't be 2, 3 or 5", digit))),
         }
     }
-    digit_letters
+    let wires_to_digits: HashMap<String, u32> = digit_letters
         .into_iter()
-        .map(|(digit, letters)| {
-            (
-                letters.into_iter().sorted().collect(),
-                from_digit(digit as u32, 10).unwrap(),
-            )
+        .map(|(digit, letters)| (letters.into_iter().sorted().collect(), digit))
+        .collect();
+    if wires_to_digits.len() != 10 {
+        return Err(line.error("the patterns don't make ten different digits"));
+    }
+    Ok(wires_to_digits)
+}
+
+fn sum_outputs(entries: &[Entry]) -> Result<u32> {
+    entries
+        .iter()
+        .map(|entry| {
+            let wires_to_digits = find_digits(entry)?;
+            entry.outputs.iter().try_fold(0, |value, output| {
+                let sorted_chars: String = output.chars().sorted().collect();
+                let digit = wires_to_digits.get(&sorted_chars).ok_or_else(|| {
+                    entry
+                        .line
+                        .error(format!("output {:?} matches none of the patterns", output))
+                })?;
+                Ok(value * 10 + digit)
+            })
         })
-        .collect()
+        .sum()
 }
 
-pub fn solution_2<P>(filename: P) -> u32
+pub fn solution_2<I>(input: I) -> Result<u32>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let lines = read_lines(filename).expect("failed to read input");
-    lines
-        .into_iter()
-        .map(|line_res| {
-            let line = line_res.unwrap();
-            let mut digit_output = line.split_terminator(" | ");
-            (
-                digit_output
-                    .next()
-                    .unwrap()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>(),
-                digit_output
-                    .next()
-                    .unwrap()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>(),
-            )
-        })
-        .map(|(digits, outputs)| {
-            let wires_to_digits = find_digits(digits);
-            outputs
-                .into_iter()
-                .map(move |output| {
-                    let sorted_chars: String = output.chars().sorted().collect();
-                    *wires_to_digits
-                        .get(&sorted_chars)
-                        .expect("no sorted char in wires_to_digits")
-                })
-                .collect::<String>()
-                .parse::<u32>()
-                .unwrap()
-        })
-        .sum()
+    sum_outputs(&parse_entries(input)?)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Puzzle = Vec<Entry>;
+    type Answer1 = usize;
+    type Answer2 = u32;
+
+    fn parse(&self, input: AnyLines) -> Result<Vec<Entry>> {
+        parse_entries(input)
+    }
+
+    fn part_1(&self, entries: &Vec<Entry>) -> Result<usize> {
+        Ok(count_unique_outputs(entries))
+    }
+
+    fn part_2(&self, entries: &Vec<Entry>) -> Result<u32> {
+        sum_outputs(entries)
+    }
 }
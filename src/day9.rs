@@ -1,114 +1,80 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    path::Path,
-};
+use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
 
-use crate::util::read_lines;
+use crate::{
+    error::{Error, Result},
+    input::{AnyLines, Input},
+    solution::Solution,
+    util::{parse_digits, Coord, Grid},
+};
 
-pub fn solution_1<P>(filename: P) -> u32
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    let vals: HashMap<(i32, i32), u32> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row, line)| {
-            line.unwrap()
-                .chars()
-                .enumerate()
-                .map(|(col, c)| ((row as i32, col as i32), c.to_digit(10).unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-    const DIFFS: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+fn low_points(vals: &Grid<u32>) -> impl Iterator<Item = Coord> + '_ {
     vals.iter()
-        .filter_map(|((x, y), height)| {
-            if DIFFS
-                .iter()
-                .all(|(offx, offy)| match vals.get(&(x + offx, y + offy)) {
-                    None => true,
-                    Some(neighbor_height) => neighbor_height > height,
-                })
-            {
-                Some(height)
-            } else {
-                None
-            }
+        .filter(move |&(coord, height)| {
+            vals.neighbours(coord)
+                .all(|neighbor| vals[neighbor] > *height)
         })
-        .map(|height| *height + 1)
-        .sum()
+        .map(|(coord, _)| coord)
 }
 
-pub fn solution_2<P>(filename: P) -> usize
-where
-    P: AsRef<Path>,
-{
-    let lines = read_lines(filename).expect("failed to read input");
-    let vals: HashMap<(i32, i32), u32> = lines
-        .into_iter()
-        .enumerate()
-        .map(|(row, line)| {
-            line.unwrap()
-                .chars()
-                .enumerate()
-                .map(|(col, c)| ((row as i32, col as i32), c.to_digit(10).unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-    const DIFFS: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-
-    let low_points: Vec<(i32, i32)> = vals
-        .iter()
-        .filter_map(|((x, y), height)| {
-            if DIFFS
-                .iter()
-                .all(|(offx, offy)| match vals.get(&(x + offx, y + offy)) {
-                    None => true,
-                    Some(neighbor_height) => neighbor_height > height,
-                })
-            {
-                Some((*x, *y))
-            } else {
-                None
-            }
-        })
-        .collect();
+fn risk_level(vals: &Grid<u32>) -> u32 {
+    low_points(vals).map(|coord| vals[coord] + 1).sum()
+}
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut basins: HashMap<(i32, i32), usize> =
-        low_points.iter().map(|point| (*point, 0)).collect();
-    for low_point in low_points {
-        let mut queue: VecDeque<(i32, i32)> = VecDeque::from([low_point]);
-        // println!("BOUNDARY");
+fn basin_product(vals: &Grid<u32>) -> Result<usize> {
+    let mut visited: Grid<bool> = Grid::new(vals.rows(), vals.cols(), false);
+    let mut basins: HashMap<Coord, usize> = HashMap::new();
+    for low_point in low_points(vals) {
+        let mut queue: VecDeque<Coord> = VecDeque::from([low_point]);
+        // bfs
         while let Some(next_point) = queue.pop_front() {
-            // println!("{:?}", next_point);
-            // bfs
-            if visited.contains(&next_point) {
+            if visited[next_point] || vals[next_point] >= 9 {
                 continue;
             }
-            if let Some(height) = vals.get(&next_point) {
-                if *height < 9 {
-                    *(basins.entry(low_point).or_insert(0)) += 1;
-                } else {
-                    continue;
-                }
-            } else {
-                continue;
-            }
-            visited.insert(next_point);
-
-            let (x, y) = next_point;
-            DIFFS.iter().for_each(|(offx, offy)| {
-                queue.push_back((x + offx, y + offy));
-            });
+            *(basins.entry(low_point).or_insert(0)) += 1;
+            visited[next_point] = true;
+            queue.extend(vals.neighbours(next_point));
         }
     }
-    println!("{:?}", basins);
+    debug!("{:?}", basins);
     let sizes: Vec<usize> = basins.drain().map(|(_, v)| v).sorted().rev().collect();
-    sizes[0] * sizes[1] * sizes[2]
+    if sizes.len() < 3 {
+        return Err(Error::NoSolution(format!("only {} basins", sizes.len())));
+    }
+    Ok(sizes[0] * sizes[1] * sizes[2])
+}
+
+pub fn solution_1<I>(input: I) -> Result<u32>
+where
+    I: Input,
+{
+    Ok(risk_level(&parse_digits(input)?))
+}
+
+pub fn solution_2<I>(input: I) -> Result<usize>
+where
+    I: Input,
+{
+    basin_product(&parse_digits(input)?)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Puzzle = Grid<u32>;
+    type Answer1 = u32;
+    type Answer2 = usize;
+
+    fn parse(&self, input: AnyLines) -> Result<Grid<u32>> {
+        parse_digits(input)
+    }
+
+    fn part_1(&self, vals: &Grid<u32>) -> Result<u32> {
+        Ok(risk_level(vals))
+    }
+
+    fn part_2(&self, vals: &Grid<u32>) -> Result<usize> {
+        basin_product(vals)
+    }
 }
@@ -0,0 +1,53 @@
+use std::{fmt, io};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    // the input couldn't be read at all
+    Io {
+        source: String,
+        error: io::Error,
+    },
+    // a single line of the input is malformed
+    Parse {
+        source: String,
+        line: usize,
+        text: String,
+        message: String,
+    },
+    // the input as a whole is malformed, e.g. it ended early
+    Input {
+        source: String,
+        message: String,
+    },
+    // the input parsed fine, but the puzzle has no answer for it
+    NoSolution(String),
+    NoSuchPart(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { source, error } => write!(f, "{}: {}", source, error),
+            Error::Parse {
+                source,
+                line,
+                text,
+                message,
+            } => write!(f, "{}:{}: {} in {:?}", source, line, message, text),
+            Error::Input { source, message } => write!(f, "{}: {}", source, message),
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::NoSuchPart(part) => write!(f, "no part {} for this day", part),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Stdin},
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use crate::error::{Error, Result};
+
+// a line of puzzle input that remembers where it came from, so parse errors can point at it
+#[derive(Debug, Clone)]
+pub struct Line {
+    source: Arc<str>,
+    number: usize,
+    text: String,
+}
+
+impl Line {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.parse_field(&self.text)
+    }
+
+    // parses a piece of this line, e.g. one of its comma separated numbers
+    pub fn parse_field<T>(&self, field: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        field
+            .trim()
+            .parse()
+            .map_err(|err| self.error(format!("couldn't parse {:?}: {}", field, err)))
+    }
+
+    pub fn error<M>(&self, message: M) -> Error
+    where
+        M: Into<String>,
+    {
+        Error::Parse {
+            source: self.source.to_string(),
+            line: self.number,
+            text: self.text.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Deref for Line {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+// lines from any reader, so the registry doesn't need to know where the input came from
+pub type AnyLines = Lines<Box<dyn BufRead>>;
+
+pub struct Lines<R> {
+    source: Arc<str>,
+    lines: io::Lines<R>,
+    number: usize,
+}
+
+impl<R> Lines<R>
+where
+    R: BufRead,
+{
+    pub fn new(source: &str, reader: R) -> Self {
+        Self {
+            source: source.into(),
+            lines: reader.lines(),
+            number: 0,
+        }
+    }
+
+    // like `next`, but running out of input is an error
+    pub fn next_line(&mut self) -> Result<Line> {
+        match self.next() {
+            Some(line) => line,
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    // an error about the input as a whole rather than any one line
+    pub fn error<M>(&self, message: M) -> Error
+    where
+        M: Into<String>,
+    {
+        Error::Input {
+            source: self.source.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl<R> Iterator for Lines<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Line>;
+
+    fn next(&mut self) -> Option<Result<Line>> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(
+            text.map(|text| Line {
+                source: self.source.clone(),
+                number: self.number,
+                text,
+            })
+            .map_err(|error| Error::Io {
+                source: self.source.to_string(),
+                error,
+            }),
+        )
+    }
+}
+
+pub fn read_lines<P>(filename: P) -> Result<Lines<BufReader<File>>>
+where
+    P: AsRef<Path>,
+{
+    let source = filename.as_ref().display().to_string();
+    let file = File::open(&filename).map_err(|error| Error::Io {
+        source: source.clone(),
+        error,
+    })?;
+    Ok(Lines::new(&source, BufReader::new(file)))
+}
+
+pub fn source_name(path: &Path) -> String {
+    if path == Path::new("-") {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+// "-" reads from stdin, anything else is a file
+pub fn open(path: &Path) -> Result<AnyLines> {
+    if path == Path::new("-") {
+        return Ok(Lines::new("<stdin>", Box::new(io::stdin().lock())));
+    }
+    let file = File::open(path).map_err(|error| Error::Io {
+        source: source_name(path),
+        error,
+    })?;
+    Ok(Lines::new(
+        &source_name(path),
+        Box::new(BufReader::new(file)),
+    ))
+}
+
+// the whole input up front, for when it has to be hashed before it's parsed
+pub fn read_all(path: &Path) -> Result<String> {
+    let mut text = String::new();
+    let read = if path == Path::new("-") {
+        io::stdin().read_to_string(&mut text)
+    } else {
+        File::open(path).and_then(|mut file| file.read_to_string(&mut text))
+    };
+    read.map_err(|error| Error::Io {
+        source: source_name(path),
+        error,
+    })?;
+    Ok(text)
+}
+
+// anything a day can read its puzzle from: the input itself as a string, a path to it,
+// stdin, or the lines of any other reader
+pub trait Input {
+    type Reader: BufRead;
+
+    fn into_lines(self) -> Result<Lines<Self::Reader>>;
+}
+
+impl<'a> Input for &'a str {
+    type Reader = &'a [u8];
+
+    fn into_lines(self) -> Result<Lines<&'a [u8]>> {
+        Ok(Lines::new("<str>", self.as_bytes()))
+    }
+}
+
+impl Input for &Path {
+    type Reader = BufReader<File>;
+
+    fn into_lines(self) -> Result<Lines<BufReader<File>>> {
+        read_lines(self)
+    }
+}
+
+impl Input for PathBuf {
+    type Reader = BufReader<File>;
+
+    fn into_lines(self) -> Result<Lines<BufReader<File>>> {
+        read_lines(self)
+    }
+}
+
+impl Input for Stdin {
+    type Reader = io::StdinLock<'static>;
+
+    fn into_lines(self) -> Result<Lines<io::StdinLock<'static>>> {
+        Ok(Lines::new("<stdin>", self.lock()))
+    }
+}
+
+impl<R> Input for Lines<R>
+where
+    R: BufRead,
+{
+    type Reader = R;
+
+    fn into_lines(self) -> Result<Lines<R>> {
+        Ok(self)
+    }
+}
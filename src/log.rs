@@ -0,0 +1,60 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+// diagnostics go to stderr so they never mix with the answers on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet = 0,
+    Info = 1,
+    Debug = 2,
+    Trace = 3,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "quiet" => Ok(Level::Quiet),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level: {}", level)),
+        }
+    }
+}
+
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// progress and timings that are useful on every run
+macro_rules! info {
+    ($($arg:tt)*) => { log_at!($crate::log::Level::Info, $($arg)*) };
+}
+
+// intermediate results, e.g. what the input parsed into
+macro_rules! debug {
+    ($($arg:tt)*) => { log_at!($crate::log::Level::Debug, $($arg)*) };
+}
+
+// per-step output from inside a solution's loops
+macro_rules! trace {
+    ($($arg:tt)*) => { log_at!($crate::log::Level::Trace, $($arg)*) };
+}
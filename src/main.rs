@@ -3,6 +3,17 @@
 #[macro_use]
 extern crate derive_more;
 
+#[macro_use]
+mod log;
+
+mod alignment;
+mod alu;
+mod animate;
+mod answers;
+mod bench;
+mod bits;
+mod boxes;
+mod cli;
 mod day1;
 mod day10;
 mod day11;
@@ -28,74 +39,20 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod error;
+mod input;
+mod parallel;
+mod render;
+mod snailfish;
+mod solution;
+mod store;
+#[cfg(test)]
+mod tests;
 mod util;
 
-#[allow(unused_imports)]
-use crate::util::read_lines;
-
 fn main() {
-    // println!("{}", day1::solution_1("input/day1_input.txt"));
-    // println!("{}", day1::solution_2("input/day1_input.txt"));
-    // println!("{}", day2::solution_1("input/day2_input.txt"));
-    // println!("{}", day2::solution_2("input/day2_input.txt"));
-    // println!("{}", day3::solution_1("input/day3_input.txt"));
-    // println!("{}", day3::solution_2("input/day3_input.txt"));
-    // println!("{}", day4::solution_1("input/day4_input.txt"));
-    // println!("{}", day4::solution_2("input/day4_input.txt"));
-    // println!("{}", day5::solution_1("input/day5_input.txt"));
-    // println!("{}", day5::solution_2("input/day5_input.txt"));
-    // println!("{}", day6::solution_1("input/day6_input.txt"));
-    // println!("{}", day6::solution_2("input/day6_input.txt"));
-    // println!("{}", day7::solution_1("input/day7_input.txt"));
-    // println!("{}", day7::solution_2("input/day7_input.txt"));
-    // println!("{}", day8::solution_1("input/day8_input.txt"));
-    // println!("{}", day8::solution_2("input/day8_input.txt"));
-    // println!("{}", day9::solution_1("input/day9_input.txt"));
-    // println!("{}", day9::solution_2("input/day9_input.txt"));
-    // println!("{}", day10::solution_1("input/day10_input.txt"));
-    // println!("{}", day10::solution_2("input/day10_input.txt"));
-    // println!("{}", day11::solution_1("input/day11_input.txt", 100));
-    // println!("{}", day11::solution_2("input/day11_input.txt"));
-    // println!("{}", day12::solution_1("input/day12_input.txt"));
-    // println!("{}", day12::solution_2("input/day12_input.txt"));
-    // println!("{}", day13::solution_1("input/day13_input.txt"));
-    // println!("{}", day13::solution_2("input/day13_input.txt"));
-    // println!("{}", day14::solution_1("input/day14_input.txt"));
-    // println!("{}", day14::solution_2("input/day14_input.txt"));
-    // println!("{}", day15::solution_1("input/day15_input.txt"));
-    // println!("{}", day15::solution_2("input/day15_input.txt"));
-    // {
-    //     let lines = read_lines("input/day16_input.txt").expect("failed to read input");
-    //     let scores: Vec<u32> = lines
-    //         .into_iter()
-    //         .map(|l| day16::solution_1(&l.unwrap()))
-    //         .collect();
-    //     println!("{:?}", scores);
-    // }
-    // {
-    //     let lines = read_lines("input/day16_input.txt").expect("failed to read input");
-    //     let scores: Vec<_> = lines
-    //         .into_iter()
-    //         .map(|l| day16::solution_2(&l.unwrap()))
-    //         .collect();
-    //     println!("{:?}", scores);
-    // }
-    // println!("{}", day17::solution_1((244, 303), (-91, -54)));
-    // println!("{}", day17::solution_2((20, 30), (-10, -5)));
-    // println!("{}", day17::solution_2((244, 303), (-91, -54)));
-    // println!("{}", day18::solution_1("input/day18_input.txt"));
-    // println!("{}", day18::solution_2("input/day18_input.txt"));
-    // println!("{}", day19::solution_1("input/day19_input.txt"));
-    // println!("{}", day19::solution_2("input/day19_input.txt"));
-    // println!("{}", day20::solution_1("input/day20_input.txt"));
-    // println!("{}", day20::solution_2("input/day20_input.txt"));
-    // println!("{}", day21::solution_1(8, 10));
-    // println!("{}", day21::solution_2(8, 10));
-    // println!("{}", day22::solution_1("input/day22_input.txt"));
-    // println!("{}", day22::solution_2("input/day22_input.txt"));
-    // println!("{}", day23::solution_1());
-    // println!("{}", day23::solution_2());
-    // println!("{}", day24::solution_1("input/day24_input.txt"));
-    // println!("{}", day24::solution_2("input/day24_input.txt"));
-    // println!("{}", day25::solution_1("input/day25_input.txt"));
+    if let Err(err) = cli::run(std::env::args().skip(1)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
 }
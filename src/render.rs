@@ -0,0 +1,162 @@
+use std::{char::from_digit, str::FromStr};
+
+use crate::util::Grid;
+
+// how a single cell of a grid should look, whichever format it ends up in
+pub trait Shade {
+    // what the cell looks like as ASCII art
+    fn glyph(&self) -> char;
+
+    // how bright the cell is, from 0 (dark) to 255
+    fn shade(&self) -> u8;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // plain characters, one line per row
+    Ascii,
+    // the same characters, coloured by shade for a 256 colour terminal
+    Ansi,
+    // plain PBM, one bit per cell where anything at least half bright is ink
+    Pbm,
+    // plain PGM, the shade of each cell as a grey level
+    Pgm,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "ascii" => Ok(Format::Ascii),
+            "ansi" => Ok(Format::Ansi),
+            "pbm" => Ok(Format::Pbm),
+            "pgm" => Ok(Format::Pgm),
+            _ => Err(format!("unknown render format: {}", format)),
+        }
+    }
+}
+
+impl Format {
+    pub fn render<T>(&self, grid: &Grid<T>) -> String
+    where
+        T: Shade,
+    {
+        match self {
+            Format::Ascii => ascii(grid, T::glyph),
+            Format::Ansi => ansi(grid),
+            Format::Pbm => netpbm(grid, "P1", None, |cell| (cell.shade() >= 128) as u8),
+            Format::Pgm => netpbm(grid, "P2", Some(255), T::shade),
+        }
+    }
+
+    // what to name a file holding this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Ascii | Format::Ansi => "txt",
+            Format::Pbm => "pbm",
+            Format::Pgm => "pgm",
+        }
+    }
+
+    // whether a terminal can show it as is
+    pub fn is_text(&self) -> bool {
+        matches!(self, Format::Ascii | Format::Ansi)
+    }
+}
+
+// for when the cells should look different to their usual glyphs, e.g. for an answer
+pub fn ascii<T, F>(grid: &Grid<T>, mut glyph: F) -> String
+where
+    F: FnMut(&T) -> char,
+{
+    rows(grid)
+        .map(|row| row.map(&mut glyph).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// the greys in the 256 colour palette run from 232 (nearly black) to 255 (nearly white)
+fn ansi<T>(grid: &Grid<T>) -> String
+where
+    T: Shade,
+{
+    rows(grid)
+        .map(|row| {
+            let mut line = String::new();
+            let mut colour = None;
+            for cell in row {
+                let grey = 232 + cell.shade() as u32 * 23 / 255;
+                if colour != Some(grey) {
+                    line += &format!("\x1b[38;5;{}m", grey);
+                    colour = Some(grey);
+                }
+                line.push(cell.glyph());
+            }
+            line + "\x1b[0m"
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// the plain (text) netpbm formats, which are easy to diff and most image viewers open
+fn netpbm<T, F>(grid: &Grid<T>, magic: &str, max: Option<u8>, mut value: F) -> String
+where
+    F: FnMut(&T) -> u8,
+{
+    let mut image = format!("{}\n{} {}\n", magic, grid.cols(), grid.rows());
+    if let Some(max) = max {
+        image += &format!("{}\n", max);
+    }
+    for row in rows(grid) {
+        let values: Vec<String> = row.map(|cell| value(cell).to_string()).collect();
+        image += &values.join(" ");
+        image.push('\n');
+    }
+    image
+}
+
+fn rows<T>(grid: &Grid<T>) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+    (0..grid.rows()).map(move |row| (0..grid.cols()).map(move |col| &grid[(row, col)]))
+}
+
+// lit pixels, dots on paper and the like
+impl Shade for bool {
+    fn glyph(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+
+    fn shade(&self) -> u8 {
+        if *self {
+            255
+        } else {
+            0
+        }
+    }
+}
+
+// single digit maps like heights, energy levels and risks. anything past 9 is drawn
+// as '+' at full brightness
+impl Shade for u32 {
+    fn glyph(&self) -> char {
+        from_digit(*self, 10).unwrap_or('+')
+    }
+
+    fn shade(&self) -> u8 {
+        ((*self).min(9) * 255 / 9) as u8
+    }
+}
+
+impl Shade for u64 {
+    fn glyph(&self) -> char {
+        ((*self).min(10) as u32).glyph()
+    }
+
+    fn shade(&self) -> u8 {
+        ((*self).min(9) as u32).shade()
+    }
+}
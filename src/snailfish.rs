@@ -0,0 +1,641 @@
+use std::{collections::VecDeque, fmt, iter::Sum, ops::Add, str::FromStr};
+
+// snailfish numbers from day 18, which are always a pair at the top level. `Pair` is the
+// tree the puzzle describes, `Flat` keeps just the regular numbers in order along with
+// how deeply each one is nested, which is much cheaper to explode and to clone
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Pair(Box<Pair>),
+    Lit(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    left: Node,
+    right: Node,
+}
+
+// one explode or split from reducing a sum, for debugging
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    // the pair of regular numbers that exploded
+    Explode(u32, u32),
+    // the regular number that split
+    Split(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<N> {
+    pub action: Action,
+    pub after: N,
+}
+
+// what both representations of snailfish numbers can do
+pub trait Number:
+    Clone + Eq + fmt::Display + FromStr<Err = String> + Add<Output = Self> + Sized
+{
+    // the same as `+`, along with every explode and split it took to reduce the result
+    fn add_traced(self, other: Self) -> (Self, Vec<Step<Self>>);
+
+    fn magnitude(&self) -> u64;
+}
+
+#[derive(Debug)]
+enum ParseNode {
+    Node(Node),
+    Open,
+}
+
+// regular numbers can have any number of digits and pairs can nest to any depth,
+// reduced or not
+impl FromStr for Pair {
+    type Err = String;
+    fn from_str(line: &str) -> std::result::Result<Self, Self::Err> {
+        let mut stack: VecDeque<ParseNode> = VecDeque::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '[' => {
+                    stack.push_back(ParseNode::Open);
+                }
+                ',' => (),
+                ']' => match (stack.pop_back(), stack.pop_back(), stack.pop_back()) {
+                    (
+                        Some(ParseNode::Node(right)),
+                        Some(ParseNode::Node(left)),
+                        Some(ParseNode::Open),
+                    ) => {
+                        stack.push_back(ParseNode::Node(Node::Pair(Box::new(Pair { left, right }))))
+                    }
+                    _ => return Err("unbalanced pair".to_string()),
+                },
+                _ => {
+                    let mut lit = c
+                        .to_digit(10)
+                        .ok_or_else(|| format!("unexpected character {:?}", c))?;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        lit = lit
+                            .checked_mul(10)
+                            .and_then(|lit| lit.checked_add(digit))
+                            .ok_or("regular number too large")?;
+                        chars.next();
+                    }
+                    stack.push_back(ParseNode::Node(Node::Lit(lit)))
+                }
+            }
+        }
+        match (stack.pop_back(), stack.is_empty()) {
+            (Some(ParseNode::Node(Node::Pair(pair))), true) => Ok(*pair),
+            _ => Err("expected a single pair".to_string()),
+        }
+    }
+}
+
+// the puzzle's own notation, e.g. [[1,2],3]
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{},{}]", self.left, self.right)
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Pair(p) => write!(f, "{}", p),
+            Node::Lit(l) => write!(f, "{}", l),
+        }
+    }
+}
+
+impl<N> fmt::Display for Step<N>
+where
+    N: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            Action::Explode(l, r) => write!(f, "after explode [{},{}]: {}", l, r, self.after),
+            Action::Split(l) => write!(f, "after split {}: {}", l, self.after),
+        }
+    }
+}
+
+impl Add for Pair {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut res = Self::join(self, other);
+        res.reduce(None);
+        res
+    }
+}
+
+// there's no zero to start from, so only a non-empty sum has a value
+impl Sum<Pair> for Option<Pair> {
+    fn sum<I>(mut iter: I) -> Self
+    where
+        I: Iterator<Item = Pair>,
+    {
+        let first = iter.next()?;
+        Some(iter.fold(first, |acc, new| acc + new))
+    }
+}
+
+impl<'a> Sum<&'a Pair> for Option<Pair> {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = &'a Pair>,
+    {
+        iter.cloned().sum()
+    }
+}
+
+enum Explosion {
+    // the pair that exploded, whether it's the direct child that needs replacing with 0, and
+    // the parts of it that still need adding to the regular numbers either side of it
+    Exploded {
+        pair: (u32, u32),
+        just_exploded: bool,
+        left: Option<u32>,
+        right: Option<u32>,
+    },
+    Nothing,
+}
+
+impl Pair {
+    fn join(left: Pair, right: Pair) -> Self {
+        Self {
+            left: Node::Pair(Box::new(left)),
+            right: Node::Pair(Box::new(right)),
+        }
+    }
+
+    fn reduce(&mut self, mut steps: Option<&mut Vec<Step<Self>>>) {
+        loop {
+            let action = if let Some(pair) = self.explode() {
+                Action::Explode(pair.0, pair.1)
+            } else if let Some(l) = self.split() {
+                Action::Split(l)
+            } else {
+                break;
+            };
+            if let Some(steps) = steps.as_mut() {
+                steps.push(Step {
+                    action,
+                    after: self.clone(),
+                });
+            }
+        }
+    }
+
+    fn send_left(&mut self, going_down: bool, val: &u32) -> bool {
+        if !going_down {
+            match &mut self.right {
+                Node::Lit(curr) => {
+                    *curr += *val;
+                    return false;
+                }
+                Node::Pair(p) => {
+                    let cont = (*p).send_left(false, val);
+                    if !cont {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        match &mut self.left {
+            Node::Lit(curr) => {
+                *curr += *val;
+                false
+            }
+            Node::Pair(p) => (*p).send_left(false, val),
+        }
+    }
+
+    fn send_right(&mut self, going_down: bool, val: &u32) -> bool {
+        if !going_down {
+            match &mut self.left {
+                Node::Lit(curr) => {
+                    *curr += *val;
+                    return false;
+                }
+                Node::Pair(p) => {
+                    let cont = (*p).send_right(false, val);
+                    if !cont {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        match &mut self.right {
+            Node::Lit(curr) => {
+                *curr += *val;
+                false
+            }
+            Node::Pair(p) => (*p).send_right(false, val),
+        }
+    }
+
+    // `depth` counts down to 0 at the pairs nested inside four others. anything nested
+    // deeper than that is only possible in unreduced input, and the leftmost pair of
+    // regular numbers among them explodes first
+    fn explode_helper(&mut self, depth: u32) -> Explosion {
+        if depth == 0 {
+            if let (Node::Lit(l), Node::Lit(r)) = (&self.left, &self.right) {
+                return Explosion::Exploded {
+                    pair: (*l, *r),
+                    just_exploded: true,
+                    left: Some(*l),
+                    right: Some(*r),
+                };
+            }
+        }
+        let depth = depth.saturating_sub(1);
+        match &mut self.left {
+            Node::Lit(_) => (),
+            Node::Pair(p) => match p.explode_helper(depth) {
+                Explosion::Exploded {
+                    pair,
+                    just_exploded,
+                    left,
+                    right,
+                } => {
+                    if just_exploded {
+                        self.left = Node::Lit(0);
+                    }
+                    let bubble_right = { right.filter(|&right| self.send_right(true, &right)) };
+                    return Explosion::Exploded {
+                        pair,
+                        just_exploded: false,
+                        left,
+                        right: bubble_right,
+                    };
+                }
+                Explosion::Nothing => (),
+            },
+        }
+        match &mut self.right {
+            Node::Lit(_) => Explosion::Nothing,
+            Node::Pair(p) => match p.explode_helper(depth) {
+                Explosion::Exploded {
+                    pair,
+                    just_exploded,
+                    left,
+                    right,
+                } => {
+                    if just_exploded {
+                        self.right = Node::Lit(0);
+                    }
+                    let bubble_left = { left.filter(|&left| self.send_left(true, &left)) };
+                    Explosion::Exploded {
+                        pair,
+                        just_exploded: false,
+                        left: bubble_left,
+                        right,
+                    }
+                }
+                Explosion::Nothing => Explosion::Nothing,
+            },
+        }
+    }
+
+    // the pair that exploded, if any
+    fn explode(&mut self) -> Option<(u32, u32)> {
+        match self.explode_helper(4) {
+            Explosion::Nothing => None,
+            Explosion::Exploded { pair, .. } => Some(pair),
+        }
+    }
+
+    // the regular number that split, if any
+    fn split(&mut self) -> Option<u32> {
+        match &mut self.left {
+            &mut Node::Lit(l) => {
+                if l >= 10 {
+                    self.left = Node::Pair(Box::new(Pair {
+                        left: Node::Lit(l / 2),
+                        right: Node::Lit(l.div_ceil(2)),
+                    }));
+                    return Some(l);
+                }
+            }
+            Node::Pair(p) => {
+                if let Some(l) = p.split() {
+                    return Some(l);
+                }
+            }
+        }
+        match &mut self.right {
+            &mut Node::Lit(l) => {
+                if l >= 10 {
+                    self.right = Node::Pair(Box::new(Pair {
+                        left: Node::Lit(l / 2),
+                        right: Node::Lit(l.div_ceil(2)),
+                    }));
+                    return Some(l);
+                }
+            }
+            Node::Pair(p) => {
+                if let Some(l) = p.split() {
+                    return Some(l);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Number for Pair {
+    fn add_traced(self, other: Self) -> (Self, Vec<Step<Self>>) {
+        let mut res = Self::join(self, other);
+        let mut steps = Vec::new();
+        res.reduce(Some(&mut steps));
+        (res, steps)
+    }
+
+    fn magnitude(&self) -> u64 {
+        self.left.magnitude() * 3 + self.right.magnitude() * 2
+    }
+}
+
+impl Node {
+    fn magnitude(&self) -> u64 {
+        match self {
+            Node::Pair(p) => p.magnitude(),
+            Node::Lit(l) => *l as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Regular {
+    // how many pairs this number is inside, so 1 for either half of the outermost pair
+    depth: u32,
+    value: u32,
+}
+
+// how deeply `Flat` can nest, so that positions fit in a u64
+const MAX_DEPTH: u32 = 63;
+
+// where each regular number starts, as a binary fraction of the way across the number
+// with MAX_DEPTH bits after the point. a regular number `depth` pairs down spans
+// 2^-depth of the number
+fn width(depth: u32) -> u64 {
+    1 << (MAX_DEPTH - depth)
+}
+
+fn is_left_half(position: u64, depth: u32) -> bool {
+    position & width(depth) == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flat {
+    regulars: Vec<Regular>,
+}
+
+// the same syntax as `Pair`, but only nested up to MAX_DEPTH - 1 deep, which leaves room
+// for the extra pair adding two numbers puts around them
+impl FromStr for Flat {
+    type Err = String;
+    fn from_str(line: &str) -> std::result::Result<Self, Self::Err> {
+        let mut regulars = Vec::new();
+        // how many elements each of the pairs we're inside has so far
+        let mut open: Vec<u32> = Vec::new();
+        let mut finished = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if finished {
+                return Err("expected a single pair".to_string());
+            }
+            match c {
+                '[' if open.len() as u32 == MAX_DEPTH - 1 => {
+                    return Err(format!("nested more than {} pairs deep", MAX_DEPTH - 1))
+                }
+                '[' => open.push(0),
+                ',' => (),
+                ']' => {
+                    if open.pop() != Some(2) {
+                        return Err("unbalanced pair".to_string());
+                    }
+                    match open.last_mut() {
+                        Some(elements) => *elements += 1,
+                        None => finished = true,
+                    }
+                }
+                _ => {
+                    let mut value = c
+                        .to_digit(10)
+                        .ok_or_else(|| format!("unexpected character {:?}", c))?;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        value = value
+                            .checked_mul(10)
+                            .and_then(|value| value.checked_add(digit))
+                            .ok_or("regular number too large")?;
+                        chars.next();
+                    }
+                    match open.last_mut() {
+                        Some(elements) => *elements += 1,
+                        None => return Err("expected a single pair".to_string()),
+                    }
+                    regulars.push(Regular {
+                        depth: open.len() as u32,
+                        value,
+                    });
+                }
+            }
+            if open.last().is_some_and(|elements| *elements > 2) {
+                return Err("unbalanced pair".to_string());
+            }
+        }
+        if !finished {
+            return Err("expected a single pair".to_string());
+        }
+        Ok(Flat { regulars })
+    }
+}
+
+impl fmt::Display for Flat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut regulars = self.regulars.iter().peekable();
+        self.fmt_node(f, &mut regulars, 0)
+    }
+}
+
+impl Add for Flat {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut res = Self::join(self, other);
+        res.reduce(None);
+        res
+    }
+}
+
+impl Sum<Flat> for Option<Flat> {
+    fn sum<I>(mut iter: I) -> Self
+    where
+        I: Iterator<Item = Flat>,
+    {
+        let first = iter.next()?;
+        Some(iter.fold(first, |acc, new| acc + new))
+    }
+}
+
+impl<'a> Sum<&'a Flat> for Option<Flat> {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = &'a Flat>,
+    {
+        iter.cloned().sum()
+    }
+}
+
+impl Flat {
+    // the node `depth` pairs down that starts at the next regular number, which is that
+    // number itself if it's at this depth and a pair otherwise
+    fn fmt_node<'a, I>(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        regulars: &mut std::iter::Peekable<I>,
+        depth: u32,
+    ) -> fmt::Result
+    where
+        I: Iterator<Item = &'a Regular>,
+    {
+        match regulars.peek() {
+            Some(regular) if regular.depth == depth => {
+                write!(f, "{}", regular.value)?;
+                regulars.next();
+                Ok(())
+            }
+            _ => {
+                write!(f, "[")?;
+                self.fmt_node(f, regulars, depth + 1)?;
+                write!(f, ",")?;
+                self.fmt_node(f, regulars, depth + 1)?;
+                write!(f, "]")
+            }
+        }
+    }
+
+    fn join(left: Flat, right: Flat) -> Self {
+        let mut regulars = left.regulars;
+        regulars.extend(right.regulars);
+        regulars.iter_mut().for_each(|regular| regular.depth += 1);
+        Self { regulars }
+    }
+
+    // reduces in one pass rather than rescanning from the start after every action, which
+    // works because of the order actions have to happen in. explosions never make anything
+    // deeper, so they can all happen left to right first, backing up one place after each
+    // since the 0 left behind might pair up with its neighbour. after that nothing is
+    // deeper than four pairs, so the only explosion a split can cause is of the pair it
+    // just made, and afterwards the leftmost number that might need splitting is the one
+    // the explosion added to
+    fn reduce(&mut self, mut steps: Option<&mut Vec<Step<Self>>>) {
+        let mut record = |number: &Self, action| {
+            if let Some(steps) = steps.as_mut() {
+                steps.push(Step {
+                    action,
+                    after: number.clone(),
+                });
+            }
+        };
+
+        let (mut i, mut position) = (0, 0);
+        while i + 1 < self.regulars.len() {
+            let depth = self.regulars[i].depth;
+            if depth > 4 && is_left_half(position, depth) && self.regulars[i + 1].depth == depth {
+                let pair = self.explode_at(i);
+                record(self, Action::Explode(pair.0, pair.1));
+                if i > 0 {
+                    i -= 1;
+                    position -= width(self.regulars[i].depth);
+                }
+            } else {
+                position += width(depth);
+                i += 1;
+            }
+        }
+
+        let mut i = 0;
+        while i < self.regulars.len() {
+            let Regular { depth, value } = self.regulars[i];
+            if value < 10 {
+                i += 1;
+                continue;
+            }
+            self.split_at(i);
+            record(self, Action::Split(value));
+            if depth + 1 > 4 {
+                let pair = self.explode_at(i);
+                record(self, Action::Explode(pair.0, pair.1));
+                i = i.saturating_sub(1);
+            }
+        }
+    }
+
+    // replaces the pair of regular numbers starting at `i` with 0
+    fn explode_at(&mut self, i: usize) -> (u32, u32) {
+        let (left, right) = (self.regulars[i], self.regulars[i + 1]);
+        if i > 0 {
+            self.regulars[i - 1].value += left.value;
+        }
+        if let Some(next) = self.regulars.get_mut(i + 2) {
+            next.value += right.value;
+        }
+        self.regulars[i] = Regular {
+            depth: left.depth - 1,
+            value: 0,
+        };
+        self.regulars.remove(i + 1);
+        (left.value, right.value)
+    }
+
+    fn split_at(&mut self, i: usize) {
+        let Regular { depth, value } = self.regulars[i];
+        self.regulars[i] = Regular {
+            depth: depth + 1,
+            value: value / 2,
+        };
+        self.regulars.insert(
+            i + 1,
+            Regular {
+                depth: depth + 1,
+                value: value.div_ceil(2),
+            },
+        );
+    }
+
+    fn magnitude_node<'a, I>(regulars: &mut std::iter::Peekable<I>, depth: u32) -> u64
+    where
+        I: Iterator<Item = &'a Regular>,
+    {
+        match regulars.peek() {
+            Some(regular) if regular.depth == depth => {
+                let value = regular.value as u64;
+                regulars.next();
+                value
+            }
+            _ => {
+                let left = Self::magnitude_node(regulars, depth + 1);
+                let right = Self::magnitude_node(regulars, depth + 1);
+                left * 3 + right * 2
+            }
+        }
+    }
+}
+
+impl Number for Flat {
+    fn add_traced(self, other: Self) -> (Self, Vec<Step<Self>>) {
+        let mut res = Self::join(self, other);
+        let mut steps = Vec::new();
+        res.reduce(Some(&mut steps));
+        (res, steps)
+    }
+
+    fn magnitude(&self) -> u64 {
+        Self::magnitude_node(&mut self.regulars.iter().peekable(), 0)
+    }
+}
@@ -0,0 +1,99 @@
+use std::{any::Any, fmt::Display};
+
+use crate::error::{Error, Result};
+use crate::input::AnyLines;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+
+// a day's puzzle: the input is parsed once, then each part is solved from the parsed puzzle
+pub trait Solution {
+    type Puzzle;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    // day 25 only has a single part
+    const PARTS: u32 = 2;
+
+    fn parse(&self, input: AnyLines) -> Result<Self::Puzzle>;
+    fn part_1(&self, puzzle: &Self::Puzzle) -> Result<Self::Answer1>;
+    fn part_2(&self, puzzle: &Self::Puzzle) -> Result<Self::Answer2>;
+}
+
+// type-erased `Solution` so that every day can live in the same registry
+pub trait AnySolution {
+    fn parts(&self) -> u32;
+    fn parse(&self, input: AnyLines) -> Result<Box<dyn Any>>;
+    fn solve(&self, puzzle: &dyn Any, part: u32) -> Result<String>;
+
+    fn run(&self, input: AnyLines, part: u32) -> Result<String> {
+        if part < 1 || part > self.parts() {
+            return Err(Error::NoSuchPart(part));
+        }
+        let puzzle = self.parse(input)?;
+        self.solve(puzzle.as_ref(), part)
+    }
+}
+
+impl<S> AnySolution for S
+where
+    S: Solution,
+    S::Puzzle: 'static,
+{
+    fn parts(&self) -> u32 {
+        S::PARTS
+    }
+
+    fn parse(&self, input: AnyLines) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse(self, input)?))
+    }
+
+    fn solve(&self, puzzle: &dyn Any, part: u32) -> Result<String> {
+        let puzzle = puzzle
+            .downcast_ref::<S::Puzzle>()
+            .expect("puzzle parsed by a different day");
+        match part {
+            1 => Ok(self.part_1(puzzle)?.to_string()),
+            2 if S::PARTS >= 2 => Ok(self.part_2(puzzle)?.to_string()),
+            _ => Err(Error::NoSuchPart(part)),
+        }
+    }
+}
+
+pub fn registry() -> Vec<(u32, Box<dyn AnySolution>)> {
+    vec![
+        (1, Box::new(day1::Day1)),
+        (2, Box::new(day2::Day2)),
+        (3, Box::new(day3::Day3)),
+        (4, Box::new(day4::Day4)),
+        (5, Box::new(day5::Day5)),
+        (6, Box::new(day6::Day6)),
+        (7, Box::new(day7::Day7)),
+        (8, Box::new(day8::Day8)),
+        (9, Box::new(day9::Day9)),
+        (10, Box::new(day10::Day10)),
+        (11, Box::new(day11::Day11)),
+        (12, Box::new(day12::Day12)),
+        (13, Box::new(day13::Day13)),
+        (14, Box::new(day14::Day14)),
+        (15, Box::new(day15::Day15)),
+        (16, Box::new(day16::Day16)),
+        (17, Box::new(day17::Day17)),
+        (18, Box::new(day18::Day18)),
+        (19, Box::new(day19::Day19)),
+        (20, Box::new(day20::Day20)),
+        (21, Box::new(day21::Day21)),
+        (22, Box::new(day22::Day22)),
+        (23, Box::new(day23::Day23)),
+        (24, Box::new(day24::Day24)),
+        (25, Box::new(day25::Day25)),
+    ]
+}
+
+pub fn lookup(day: u32) -> Option<Box<dyn AnySolution>> {
+    registry()
+        .into_iter()
+        .find(|(registered, _)| *registered == day)
+        .map(|(_, solution)| solution)
+}
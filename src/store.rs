@@ -0,0 +1,77 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use itertools::Itertools;
+
+use crate::{
+    error::{Error, Result},
+    input::{read_lines, Line},
+};
+
+// (day, part, hash of the input)
+pub type Key = (u32, u32, u64);
+
+// a value kept per day, part and input in a tab separated file, one line each:
+// day, part, input hash, then the value's own fields
+pub trait Record: Sized {
+    // how many fields the value takes up after the key
+    const FIELDS: usize;
+
+    fn parse(line: &Line, fields: &[&str]) -> Result<Self>;
+
+    fn fields(&self) -> Vec<String>;
+}
+
+// FNV-1a, since std's hasher isn't guaranteed to be stable between Rust versions
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// a missing file is just an empty one
+pub fn load<R>(path: &Path) -> Result<BTreeMap<Key, R>>
+where
+    R: Record,
+{
+    let mut records = BTreeMap::new();
+    if !path.exists() {
+        return Ok(records);
+    }
+    for line in read_lines(path)? {
+        let line = line?;
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 3 + R::FIELDS {
+            return Err(line.error(format!("expected {} tab separated fields", 3 + R::FIELDS)));
+        }
+        let hash = u64::from_str_radix(fields[2], 16)
+            .map_err(|err| line.error(format!("couldn't parse hash {:?}: {}", fields[2], err)))?;
+        let key = (
+            line.parse_field(fields[0])?,
+            line.parse_field(fields[1])?,
+            hash,
+        );
+        records.insert(key, R::parse(&line, &fields[3..])?);
+    }
+    Ok(records)
+}
+
+pub fn save<R>(path: &Path, records: &BTreeMap<Key, R>) -> Result<()>
+where
+    R: Record,
+{
+    let source = path.display().to_string();
+    let contents: String = records
+        .iter()
+        .map(|((day, part, hash), record)| {
+            let key = vec![day.to_string(), part.to_string(), format!("{:016x}", hash)];
+            format!("{}\n", key.into_iter().chain(record.fields()).join("\t"))
+        })
+        .collect();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| Error::Io {
+            source: source.clone(),
+            error,
+        })?;
+    }
+    fs::write(path, contents).map_err(|error| Error::Io { source, error })
+}
@@ -0,0 +1,623 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::{
+    alignment::{align, Config, Point, Rotation, Scan, Transform},
+    alu::{self, AluError, Register, Registers},
+    bits::{self, BitsError, EvalError, LengthType, Packet},
+    boxes::{BoxSet, Cuboid},
+    day18::{largest_sum, LargestSum},
+    day22,
+    day24::{self, Assumption},
+    day3, day4, day7, day8, input,
+    snailfish::{Action, Flat, Number, Pair},
+    solution,
+};
+
+// (day, part, answer) for the inputs in input/examples, taken from the puzzle text.
+// day 16 runs both parts over every example transmission, so the answers the puzzle
+// doesn't give for the other part are our own. day 24 doesn't have an example, so its
+// program was generated and checked against the generator's own answers instead
+const EXAMPLES: &[(u32, u32, &str)] = &[
+    (1, 1, "7"),
+    (1, 2, "5"),
+    (2, 1, "150"),
+    (2, 2, "900"),
+    (3, 1, "198"),
+    (3, 2, "230"),
+    (4, 1, "4512"),
+    (4, 2, "1924"),
+    (5, 1, "5"),
+    (5, 2, "12"),
+    (6, 1, "5934"),
+    (6, 2, "26984457539"),
+    (7, 1, "37"),
+    (7, 2, "168"),
+    (8, 1, "26"),
+    (8, 2, "61229"),
+    (9, 1, "15"),
+    (9, 2, "1134"),
+    (10, 1, "26397"),
+    (10, 2, "288957"),
+    (11, 1, "1656"),
+    (11, 2, "195"),
+    (12, 1, "10"),
+    (12, 2, "36"),
+    (13, 1, "17"),
+    (13, 2, "xxxxx\nx   x\nx   x\nx   x\nxxxxx"),
+    (14, 1, "1588"),
+    (14, 2, "2188189693529"),
+    (15, 1, "40"),
+    (15, 2, "315"),
+    (16, 1, "16\n12\n23\n31\n14\n8\n15\n11\n13\n19\n16\n20"),
+    (16, 2, "15\n46\n46\n54\n3\n54\n7\n9\n1\n0\n0\n1"),
+    (17, 1, "45"),
+    (17, 2, "112"),
+    (18, 1, "4140"),
+    (18, 2, "3993"),
+    (19, 1, "79"),
+    (19, 2, "3621"),
+    (20, 1, "35"),
+    (20, 2, "3351"),
+    (21, 1, "739785"),
+    (21, 2, "444356092776315"),
+    (22, 1, "474140"),
+    (22, 2, "2758514936282235"),
+    (23, 1, "12521"),
+    (23, 2, "44169"),
+    (24, 1, "21969398919499"),
+    (24, 2, "11417191216198"),
+    (25, 1, "58"),
+];
+
+// answers for our own puzzle inputs (input/day<n>_input.txt), one "<day> <part> <answer>"
+// per line, with "\n" standing in for the newlines of multi-line answers
+const PERSONAL_ANSWERS: &str = "input/answers.txt";
+
+fn solve(day: u32, part: u32, path: &Path) -> Result<String, String> {
+    let solution = solution::lookup(day).ok_or_else(|| format!("no solution for day {}", day))?;
+    let lines = input::open(path).map_err(|err| err.to_string())?;
+    solution.run(lines, part).map_err(|err| err.to_string())
+}
+
+// runs every (day, part, answer) and reports all the mismatches at once
+fn check<'a, I>(cases: I, input_path: fn(u32) -> String)
+where
+    I: IntoIterator<Item = (u32, u32, &'a str)>,
+{
+    let failures: Vec<String> = cases
+        .into_iter()
+        .filter_map(
+            |(day, part, expected)| match solve(day, part, Path::new(&input_path(day))) {
+                Ok(answer) if answer == expected => None,
+                Ok(answer) => Some(format!(
+                    "day {} part {}: expected {:?}, got {:?}",
+                    day, part, expected, answer
+                )),
+                Err(err) => Some(format!("day {} part {}: {}", day, part, err)),
+            },
+        )
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn examples() {
+    check(EXAMPLES.iter().copied(), |day| {
+        format!("input/examples/day{}.txt", day)
+    });
+}
+
+#[test]
+fn every_part_has_an_example() {
+    for (day, solution) in solution::registry() {
+        for part in 1..=solution.parts() {
+            assert!(
+                EXAMPLES.iter().any(|&(d, p, _)| (d, p) == (day, part)),
+                "no example for day {} part {}",
+                day,
+                part
+            );
+        }
+    }
+}
+
+#[test]
+fn personal_inputs() {
+    let answers = match fs::read_to_string(PERSONAL_ANSWERS) {
+        Ok(answers) => answers,
+        // nobody has registered any answers on this checkout
+        Err(_) => return,
+    };
+    let cases: Vec<(u32, u32, String)> = answers
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.splitn(3, ' ');
+            let mut number = || {
+                fields
+                    .next()
+                    .and_then(|field| field.parse().ok())
+                    .unwrap_or_else(|| panic!("{}: bad line {:?}", PERSONAL_ANSWERS, line))
+            };
+            let (day, part) = (number(), number());
+            let answer = fields.next().unwrap_or_default().replace("\\n", "\n");
+            (day, part, answer)
+        })
+        .collect();
+    check(
+        cases
+            .iter()
+            .map(|(day, part, answer)| (*day, *part, answer.as_str())),
+        |day| format!("input/day{}_input.txt", day),
+    );
+}
+
+// a small xorshift, so the packets are random but the same on every run
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn random_packet(state: &mut u64, depth: u32) -> Packet {
+    let version = (next_random(state) % 8) as u32;
+    let packet_type = (next_random(state) % 8) as u32;
+    if depth == 0 || packet_type == bits::LITERAL_TYPE {
+        let value = next_random(state) >> (next_random(state) % 64);
+        return Packet::literal(version, value);
+    }
+    let subpackets = match packet_type {
+        5..=7 => 2,
+        _ => 1 + next_random(state) % 4,
+    };
+    let subpackets = (0..subpackets)
+        .map(|_| random_packet(state, depth - 1))
+        .collect();
+    Packet::operator(version, packet_type, subpackets)
+}
+
+#[test]
+fn crabs_that_already_line_up_need_no_fuel() {
+    assert_eq!(day7::solution_1("5,5,5").unwrap(), 0);
+    assert_eq!(day7::solution_2("5,5,5").unwrap(), 0);
+}
+
+#[test]
+fn diagnostic_ratings_survive_bits_every_number_shares() {
+    assert_eq!(day3::solution_1("1\n1\n0\n0\n0").unwrap(), 0);
+    assert_eq!(day3::solution_2("10\n11").unwrap(), 6);
+}
+
+#[test]
+fn malformed_entries_point_at_their_line() {
+    let card = "1 2 3 4 5\n".repeat(5);
+    let cards = format!("1,2,3,4,5\n\n{}1 2 3 4 5\n\n{}", card, card);
+    let err = day4::solution_1(cards.as_str()).unwrap_err().to_string();
+    assert!(err.contains(":8: expected a blank line"), "{}", err);
+
+    let entry =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+    let missing_one = entry.replacen(" ab |", " abc |", 1);
+    let unknown_output = entry.replacen(" cdbaf", " cdbafg", 1);
+    for (input, message) in [
+        (missing_one, ":2: no pattern with 2 segments"),
+        (unknown_output, ":2: output \"cdbafg\" matches none"),
+    ] {
+        let err = day8::solution_2(format!("{}\n{}", entry, input).as_str())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(message), "{}", err);
+    }
+}
+
+#[test]
+fn bits_round_trip() {
+    let examples = fs::read_to_string("input/examples/day16.txt").unwrap();
+    for transmission in examples.lines() {
+        let packet = bits::parse_hex(transmission).unwrap();
+        let encoded = bits::encode_hex(&packet).unwrap();
+        assert_eq!(bits::parse_hex(&encoded), Ok(packet), "{}", transmission);
+    }
+
+    let mut state = 2021;
+    for _ in 0..1000 {
+        let packet = random_packet(&mut state, 4);
+        let mut seed = next_random(&mut state);
+        let encoded = bits::encode_with(&packet, &mut |_| match next_random(&mut seed) % 2 {
+            0 => LengthType::Bits,
+            _ => LengthType::Count,
+        })
+        .unwrap();
+        assert_eq!(bits::parse(&encoded), Ok(packet));
+    }
+}
+
+#[test]
+fn bits_encoding_errors_point_at_the_whole_transmission() {
+    let literal_operator = Packet::operator(0, bits::LITERAL_TYPE, vec![Packet::literal(0, 1)]);
+    assert_eq!(
+        bits::encode(&literal_operator),
+        Err(BitsError::LiteralOperator { offset: 0 })
+    );
+
+    // a version that doesn't fit in 3 bits, inside two operators
+    let nested = Packet::operator(
+        0,
+        0,
+        vec![Packet::operator(0, 0, vec![Packet::literal(8, 1)])],
+    );
+    let overflow = |offset| BitsError::FieldOverflow {
+        offset,
+        field: "version",
+        value: 8,
+        bits: 3,
+    };
+    assert_eq!(
+        bits::encode_with(&nested, &mut |_| LengthType::Bits),
+        Err(overflow(44))
+    );
+    assert_eq!(
+        bits::encode_with(&nested, &mut |_| LengthType::Count),
+        Err(overflow(36))
+    );
+}
+
+#[test]
+fn bits_expressions() {
+    let expression = Packet::operator(
+        0,
+        3,
+        vec![
+            Packet::operator(0, 0, vec![Packet::literal(0, 1), Packet::literal(0, 2)]),
+            Packet::operator(0, 6, vec![Packet::literal(0, 3), Packet::literal(0, 4)]),
+        ],
+    );
+    assert_eq!(expression.to_string(), "max(sum(1, 2), 3 < 4)");
+    assert_eq!(expression.evaluate(), Ok(3));
+
+    let huge = Packet::operator(
+        0,
+        1,
+        vec![Packet::literal(0, u64::MAX), Packet::literal(0, 2)],
+    );
+    assert_eq!(
+        huge.evaluate(),
+        Err(EvalError::Overflow {
+            expression: format!("product({}, 2)", u64::MAX)
+        })
+    );
+}
+
+fn check_snailfish<N>()
+where
+    N: Number + std::fmt::Debug,
+{
+    let examples = fs::read_to_string("input/examples/day18.txt").unwrap();
+    for line in examples.lines() {
+        assert_eq!(line.parse::<N>().unwrap().to_string(), line);
+    }
+
+    let a: N = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+    let b: N = "[1,1]".parse().unwrap();
+    let (sum, steps) = a.clone().add_traced(b.clone());
+    assert_eq!(sum, a + b);
+    assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    assert_eq!(
+        steps
+            .iter()
+            .map(|step| step.action.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Action::Explode(4, 3),
+            Action::Explode(8, 4),
+            Action::Split(15),
+            Action::Split(13),
+            Action::Explode(6, 7),
+        ]
+    );
+
+    // deeper than a reduced number can be, and with regular numbers that need splitting
+    let unreduced: N = "[[[[[[1,2],3],4],5],6],[17,0]]".parse().unwrap();
+    let (_, steps) = unreduced.add_traced("[0,0]".parse().unwrap());
+    assert_eq!(steps[0].action, Action::Explode(1, 2));
+    assert!(["[1,2,3]", "[1]", "[1,2]]", "[1,2][3,4]", "[a,2]"]
+        .iter()
+        .all(|bad| bad.parse::<N>().is_err()));
+}
+
+#[test]
+fn snailfish_formatting_and_trace() {
+    check_snailfish::<Pair>();
+    check_snailfish::<Flat>();
+}
+
+#[test]
+fn flat_snailfish_can_add_numbers_nested_as_deep_as_it_parses() {
+    // the 1 and 2 are `depth` pairs down
+    let nested = |depth| "[".repeat(depth) + "1,2]" + &",3]".repeat(depth - 1);
+    let deepest = nested(62);
+    let flat: Flat = deepest.parse().unwrap();
+    let pair: Pair = deepest.parse().unwrap();
+    let sum = flat + "[1,1]".parse().unwrap();
+    assert_eq!(
+        sum.to_string(),
+        (pair + "[1,1]".parse().unwrap()).to_string()
+    );
+    assert!(nested(63).parse::<Flat>().is_err());
+    assert!(nested(63).parse::<Pair>().is_ok());
+}
+
+#[test]
+fn largest_sum_is_the_same_on_any_number_of_threads() {
+    let numbers: Vec<Flat> = fs::read_to_string("input/examples/day18.txt")
+        .unwrap()
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    let expected = LargestSum {
+        first: 8,
+        second: 0,
+        magnitude: 3993,
+    };
+    for threads in [1, 2, 3, 16] {
+        assert_eq!(largest_sum(&numbers, threads), Some(expected));
+    }
+    assert_eq!(largest_sum::<Flat>(&[], 4), None);
+}
+
+#[test]
+fn alignment_recovers_how_a_scan_was_moved() {
+    let rotations = Rotation::all();
+    assert_eq!(rotations.len(), 24);
+    assert_eq!(rotations[0], Rotation::IDENTITY);
+    assert_eq!(rotations.iter().collect::<HashSet<_>>().len(), 24);
+
+    let points: Vec<Point> = (0..20)
+        .map(|i| {
+            (
+                (i * 37) % 101 - 50,
+                (i * i * 13) % 89 - 40,
+                (i * 71) % 97 - 45,
+            )
+        })
+        .collect();
+    let moved = Transform {
+        rotation: rotations[17],
+        translation: (500, -20, 1234),
+    };
+    let original = Scan::new(points.clone());
+    let moved_points: Vec<Point> = points.iter().map(|p| moved.apply(*p)).collect();
+    let back = original
+        .find_transform(
+            &Scan::new(moved_points.clone()),
+            &Config { min_overlap: 20 },
+        )
+        .unwrap();
+    for (point, moved_point) in points.iter().zip(&moved_points) {
+        assert_eq!(back.apply(*moved_point), *point);
+    }
+    assert_eq!(moved.then(&back), Transform::IDENTITY);
+}
+
+#[test]
+fn alignment_reports_scans_that_never_overlap() {
+    let points: Vec<Point> = (0..20)
+        .map(|i| {
+            (
+                (i * 37) % 101 - 50,
+                (i * i * 13) % 89 - 40,
+                (i * 71) % 97 - 45,
+            )
+        })
+        .collect();
+    let moved = Transform {
+        rotation: Rotation::all()[5],
+        translation: (-300, 40, 7),
+    };
+    let scans = [
+        Scan::new(points.clone()),
+        Scan::new(vec![(1000, 1000, 1000), (1001, 1003, 1007)]),
+        Scan::new(points.iter().map(|p| moved.apply(*p)).collect()),
+    ];
+    let alignment = align(&scans, &Config { min_overlap: 12 });
+    assert_eq!(alignment.clusters, vec![vec![0, 2], vec![1]]);
+    assert_eq!(alignment.edges().collect::<Vec<_>>(), vec![(0, 2)]);
+    assert_eq!(alignment.placements[1].cluster, 1);
+    assert!(alignment.diagnostic().is_some());
+}
+
+fn random_cuboid<const N: usize>(state: &mut u64) -> Cuboid<N> {
+    let mut ranges = [(0, 0); N];
+    for range in ranges.iter_mut() {
+        let first = (next_random(state) % 6) as i64 - 3;
+        *range = (first, first + (next_random(state) % 4) as i64);
+    }
+    Cuboid::from_inclusive(ranges)
+}
+
+fn points<const N: usize>(set: &BoxSet<N>) -> HashSet<[i64; N]> {
+    let mut points = HashSet::new();
+    for cuboid in set.cuboids() {
+        let mut point = cuboid.min;
+        'outer: loop {
+            assert!(points.insert(point), "{} overlaps another cuboid", cuboid);
+            for (axis, coord) in point.iter_mut().enumerate() {
+                *coord += 1;
+                if *coord < cuboid.max[axis] {
+                    continue 'outer;
+                }
+                *coord = cuboid.min[axis];
+            }
+            break;
+        }
+    }
+    points
+}
+
+fn check_box_set<const N: usize>(state: &mut u64) {
+    for _ in 0..100 {
+        let mut sets = [BoxSet::<N>::new(), BoxSet::new()];
+        for set in sets.iter_mut() {
+            for _ in 0..5 {
+                match next_random(state) % 3 {
+                    0 => set.remove(&random_cuboid(state)),
+                    _ => set.insert(random_cuboid(state)),
+                }
+            }
+        }
+        let [a, b] = &sets;
+        let (a_points, b_points) = (points(a), points(b));
+        assert_eq!(a.volume(), a_points.len() as u128);
+        let union = points(&a.union(b));
+        assert_eq!(union, &a_points | &b_points);
+        assert_eq!(points(&a.intersection(b)), &a_points & &b_points);
+        assert_eq!(points(&a.difference(b)), &a_points - &b_points);
+        for point in union {
+            assert_eq!(a.contains(point), a_points.contains(&point));
+        }
+        let region = random_cuboid(state);
+        let within = a_points.iter().filter(|p| region.contains(**p)).count();
+        assert_eq!(a.volume_within(&region), within as u128);
+    }
+}
+
+#[test]
+fn box_sets_match_the_points_they_cover() {
+    let mut state = 22;
+    check_box_set::<2>(&mut state);
+    check_box_set::<3>(&mut state);
+    check_box_set::<4>(&mut state);
+}
+
+#[test]
+fn initialization_ignores_cubes_outside_its_region() {
+    let steps = Path::new("input/examples/day22_initialization.txt");
+    assert_eq!(day22::solution_1(steps).unwrap(), 590784);
+    assert!(day22::solution_2(steps).unwrap() > 590784);
+}
+
+// the puzzle's first example, small enough to check cube by cube
+const SMALL_REBOOT: &str = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+
+#[test]
+fn reactor_answers_questions_about_its_state() {
+    let reactor = day22::reactor(SMALL_REBOOT).unwrap();
+    assert_eq!(reactor.lit_volume(), 39);
+    assert!(reactor.is_on([10, 10, 10]));
+    assert!(!reactor.is_on([11, 11, 11]));
+    assert_eq!(reactor.last_touched([10, 10, 10]), Some(3));
+    assert_eq!(reactor.last_touched([11, 11, 11]), Some(2));
+    assert_eq!(reactor.last_touched([0, 0, 0]), None);
+    let region = day22::parse_region("x=10..10,y=10..12,z=10..12").unwrap();
+    assert_eq!(reactor.lit_within(&region), 6);
+    assert!(day22::parse_region("x=1..2,y=3..4").is_err());
+
+    let decomposition = reactor.decomposition();
+    let rebooted = day22::reactor(decomposition.as_str()).unwrap();
+    assert_eq!(rebooted.lit_volume(), 39);
+    assert_eq!(rebooted.lit_cuboids().len(), reactor.lit_cuboids().len());
+}
+
+#[test]
+fn reactor_steps_can_be_undone() {
+    let mut state = 23;
+    let steps: Vec<(bool, day22::Cube)> = (0..30)
+        .map(|_| {
+            (
+                !next_random(&mut state).is_multiple_of(3),
+                random_cuboid(&mut state),
+            )
+        })
+        .collect();
+    let mut reactor = day22::Reactor::new(steps.clone());
+    let mut volumes = vec![0];
+    while let Some(volume) = reactor.apply() {
+        assert_eq!(volume, day22::reboot(&steps[..reactor.applied()]).volume());
+        volumes.push(volume);
+    }
+    assert_eq!(reactor.applied(), steps.len());
+    volumes.pop();
+    while let Some(volume) = reactor.undo() {
+        assert_eq!(Some(volume), volumes.pop());
+    }
+    assert_eq!(reactor.applied(), 0);
+    assert!(reactor.lit_cuboids().is_empty());
+}
+
+#[test]
+fn alu_runs_programs_from_the_puzzle() {
+    let example = fs::read_to_string("input/examples/day24.txt").unwrap();
+    let program = alu::parse_program(example.as_str()).unwrap();
+    assert_eq!(alu::assemble(&program), example);
+
+    // stores the lowest four bits of its input in w, x, y and z
+    let binary = "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2\n";
+    let program = alu::parse_program(binary).unwrap();
+    assert_eq!(alu::run(&program, [13]), Ok(Registers([1, 1, 0, 1])));
+    let trace = alu::run_traced(&program, [6]).unwrap();
+    assert_eq!(trace.len(), program.len());
+    assert_eq!(trace[2][Register::Z], 0);
+    assert_eq!(trace.last(), Some(&Registers([0, 1, 1, 0])));
+
+    assert_eq!(
+        alu::run(&program, []),
+        Err(AluError::InputExhausted { instruction: 0 })
+    );
+    let divide = alu::parse_program("inp x\ndiv w x\n").unwrap();
+    assert_eq!(
+        alu::run(&divide, [0]),
+        Err(AluError::DivideByZero { instruction: 1 })
+    );
+    assert!(alu::parse_program("add x").is_err());
+    assert!(alu::parse_program("mul q 2").is_err());
+    assert!(alu::parse_program("sub x 2").is_err());
+}
+
+#[test]
+fn monad_assumptions_are_checked_before_they_are_relied_on() {
+    let example = fs::read_to_string("input/examples/day24.txt").unwrap();
+    let program = alu::parse_program(example.as_str()).unwrap();
+    assert_eq!(day24::analyse(&program).map(|vars| vars.len()), Ok(14));
+
+    // the first block's A and second block's C changed, and an extra instruction in the third
+    let broken = example
+        .replacen("div z 1", "div z 2", 1)
+        .replacen("add y 14", "add y 40", 1)
+        .replacen("add x 11", "add x 11\nadd w 0", 1);
+    let program = alu::parse_program(broken.as_str()).unwrap();
+    let violations = day24::analyse(&program).unwrap_err();
+    let found: Vec<_> = violations
+        .iter()
+        .map(|violation| (violation.assumption, violation.block))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Assumption::Template, Some(2)),
+            (Assumption::Divisor, Some(0)),
+            (Assumption::SmallOffset, Some(1)),
+        ]
+    );
+
+    // accepts 3 then 7, and nothing else
+    let program =
+        alu::parse_program("inp w\nadd z w\ninp x\nmul z 10\nadd z x\nadd z -37").unwrap();
+    assert!(day24::analyse(&program).is_err());
+    assert_eq!(day24::search(&program, true).unwrap(), "37");
+    assert_eq!(day24::search(&program, false).unwrap(), "37");
+    // accepts any two digits that add up to 10
+    let program = alu::parse_program("inp w\ninp x\nadd z w\nadd z x\nadd z -10").unwrap();
+    assert_eq!(day24::search(&program, true).unwrap(), "91");
+    assert_eq!(day24::search(&program, false).unwrap(), "19");
+    let program = alu::parse_program("inp w\nadd z w").unwrap();
+    assert!(day24::search(&program, true).is_err());
+    // y is never used, but the mod fails for the first digits below 5
+    let program =
+        alu::parse_program("inp w\nadd y w\nadd y -5\ninp x\nmod y 1\nadd z x\nadd z -1").unwrap();
+    assert_eq!(day24::search(&program, true).unwrap(), "91");
+    assert_eq!(day24::search(&program, false).unwrap(), "51");
+}
@@ -1,11 +1,220 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::ops::{Index, IndexMut};
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+use crate::{error::Result, input::Input};
+
+// (row, col)
+pub type Coord = (usize, usize);
+
+// up, left, right, down
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+// every cell touching this one, diagonals included
+pub const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+// a dense rectangular grid, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            rows,
+            cols,
+            cells: vec![fill; rows * cols],
+        }
+    }
+
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(Coord) -> T,
+    {
+        Self {
+            rows,
+            cols,
+            cells: (0..rows)
+                .flat_map(|row| (0..cols).map(move |col| (row, col)))
+                .map(&mut f)
+                .collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (row, col): Coord) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        if self.contains(coord) {
+            Some(&self.cells[coord.0 * self.cols + coord.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        if self.contains(coord) {
+            Some(&mut self.cells[coord.0 * self.cols + coord.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    // the cell `offset` away, if that's still on the grid
+    pub fn offset(&self, (row, col): Coord, (row_off, col_off): (isize, isize)) -> Option<Coord> {
+        let coord = (
+            row.checked_add_signed(row_off)?,
+            col.checked_add_signed(col_off)?,
+        );
+        if self.contains(coord) {
+            Some(coord)
+        } else {
+            None
+        }
+    }
+
+    // the cell `offset` away, treating the grid as if it wraps around at the edges
+    pub fn wrapping_offset(&self, (row, col): Coord, (row_off, col_off): (isize, isize)) -> Coord {
+        (
+            (row as isize + row_off).rem_euclid(self.rows as isize) as usize,
+            (col as isize + col_off).rem_euclid(self.cols as isize) as usize,
+        )
+    }
+
+    pub fn neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |offset| self.offset(coord, *offset))
+    }
+
+    pub fn surrounding(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        SURROUNDING
+            .iter()
+            .filter_map(move |offset| self.offset(coord, *offset))
+    }
+
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    // repeats the grid `down` times vertically and `across` times horizontally,
+    // with `f` deciding what each copy of a cell becomes given which tile it's in
+    pub fn tile<F>(&self, down: usize, across: usize, mut f: F) -> Self
+    where
+        F: FnMut(&T, Coord) -> T,
+    {
+        Self::from_fn(self.rows * down, self.cols * across, |(row, col)| {
+            let original = &self[(row % self.rows, col % self.cols)];
+            f(original, (row / self.rows, col / self.cols))
+        })
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", coord))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", coord, rows, cols))
+    }
+}
+
+// a map of characters, one row per line, where `parse` decides what each character means
+pub fn parse_chars<I, T, F>(input: I, mut parse: F) -> Result<Grid<T>>
+where
+    I: Input,
+    F: FnMut(char) -> Option<T>,
+{
+    let mut lines = input.into_lines()?;
+    let mut cells = Vec::new();
+    let (mut rows, mut cols) = (0, None);
+    for line in &mut lines {
+        let line = line?;
+        let row = line
+            .chars()
+            .map(|c| parse(c).ok_or_else(|| line.error(format!("unexpected character {:?}", c))))
+            .collect::<Result<Vec<_>>>()?;
+        match cols {
+            Some(cols) if cols != row.len() => {
+                return Err(line.error(format!("expected {} columns like the rows above", cols)))
+            }
+            _ => cols = Some(row.len()),
+        }
+        cells.extend(row);
+        rows += 1;
+    }
+    match cols {
+        Some(cols) if cols > 0 => Ok(Grid { rows, cols, cells }),
+        _ => Err(lines.error("empty grid")),
+    }
+}
+
+// a map of single digits, like days 9, 11 and 15 use
+pub fn parse_digits<I>(input: I) -> Result<Grid<u32>>
 where
-    P: AsRef<Path>,
+    I: Input,
 {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    parse_chars(input, |c| c.to_digit(10))
 }